@@ -0,0 +1,218 @@
+//! Measures how many updates per second cloths of increasing size run at, next to the cloth as it
+//! was before points and sticks were stored as parallel arrays.
+//!
+//! Run with `cargo bench --bench update`.
+
+use std::time::{Duration, Instant};
+
+use cloth_sim::math::Vector2;
+use cloth_sim::Cloth;
+use notan::prelude::Mouse;
+
+const DT: f64 = 1.0 / 60.0;
+const WARMUP_STEPS: u32 = 50;
+const STEPS: u32 = 500;
+
+/// The cloth with every point and stick behind an `Rc<RefCell>`, cut down to what an update does
+/// without the mouse
+mod reference {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use cloth_sim::math::Vector2;
+
+    const GRAVITY: Vector2 = Vector2 { x: 0.0, y: 981.0 };
+    const CURSOR_RADIUS: f64 = 16.0;
+
+    pub struct Cloth {
+        points: Vec<Rc<RefCell<Point>>>,
+        sticks: Vec<Rc<RefCell<Stick>>>,
+        drag: f64,
+    }
+
+    impl Cloth {
+        pub fn new(width: i32, height: i32, spacing: i32, elasticity: f64) -> Self {
+            let mut points: Vec<Rc<RefCell<Point>>> = Vec::new();
+            let mut sticks = Vec::new();
+
+            for y in 0..height {
+                for x in 0..width {
+                    let point = Rc::new(RefCell::new(Point::new(Vector2::new(
+                        (x * spacing) as f64,
+                        (y * spacing) as f64,
+                    ))));
+
+                    if x != 0 {
+                        let left_point = points.last().unwrap();
+                        let stick = Rc::new(RefCell::new(Stick::new(
+                            Rc::clone(&point),
+                            Rc::clone(left_point),
+                            spacing as f64,
+                            elasticity,
+                        )));
+                        left_point.borrow_mut().sticks[0] = Some(Rc::clone(&stick));
+                        point.borrow_mut().sticks[0] = Some(Rc::clone(&stick));
+                        sticks.push(stick);
+                    }
+
+                    if y != 0 {
+                        let up_point = &points[(x + (y - 1) * width) as usize];
+                        let stick = Rc::new(RefCell::new(Stick::new(
+                            Rc::clone(&point),
+                            Rc::clone(up_point),
+                            spacing as f64,
+                            elasticity,
+                        )));
+                        up_point.borrow_mut().sticks[1] = Some(Rc::clone(&stick));
+                        point.borrow_mut().sticks[1] = Some(Rc::clone(&stick));
+                        sticks.push(stick);
+                    }
+
+                    if y == 0 && x % 2 == 0 {
+                        point.borrow_mut().pinned = true;
+                    }
+                    points.push(point);
+                }
+            }
+            Cloth {
+                points,
+                sticks,
+                drag: 0.05,
+            }
+        }
+
+        pub fn update(&mut self, dt: f64, mouse_position: Vector2) {
+            for point in &self.points {
+                let mut point = point.borrow_mut();
+                let dist_sq = (point.position - mouse_position).magnitude_squared();
+                let selected = dist_sq <= CURSOR_RADIUS * CURSOR_RADIUS;
+                point.update(dt, self.drag, GRAVITY, selected);
+            }
+
+            let mut to_remove = Vec::new();
+            for (i, stick) in self.sticks.iter().enumerate() {
+                let mut stick = stick.borrow_mut();
+                if stick.broken {
+                    to_remove.push(i);
+                }
+                stick.update();
+            }
+            for i in to_remove.iter().rev() {
+                self.sticks.remove(*i);
+            }
+        }
+    }
+
+    struct Point {
+        position: Vector2,
+        prev_position: Vector2,
+        initial_position: Vector2,
+        sticks: [Option<Rc<RefCell<Stick>>>; 2],
+        pinned: bool,
+    }
+
+    impl Point {
+        fn new(position: Vector2) -> Self {
+            Point {
+                position,
+                prev_position: position,
+                initial_position: position,
+                sticks: [None, None],
+                pinned: false,
+            }
+        }
+
+        fn update(&mut self, dt: f64, drag: f64, acceleration: Vector2, selected: bool) {
+            for stick in self.sticks.iter().flatten() {
+                stick.borrow_mut().selected = selected;
+            }
+            if self.pinned {
+                self.position = self.initial_position;
+                return;
+            }
+
+            let new_position = self.position
+                + (self.position - self.prev_position) * (1.0 - drag)
+                + acceleration * (1.0 - drag) * dt * dt;
+            self.prev_position = self.position;
+            self.position = new_position;
+        }
+    }
+
+    struct Stick {
+        p1: Rc<RefCell<Point>>,
+        p2: Rc<RefCell<Point>>,
+        length: f64,
+        elasticity: f64,
+        selected: bool,
+        broken: bool,
+    }
+
+    impl Stick {
+        fn new(
+            p1: Rc<RefCell<Point>>,
+            p2: Rc<RefCell<Point>>,
+            length: f64,
+            elasticity: f64,
+        ) -> Self {
+            Stick {
+                p1,
+                p2,
+                length,
+                elasticity,
+                selected: false,
+                broken: false,
+            }
+        }
+
+        fn update(&mut self) {
+            let offset = {
+                let p1 = self.p1.borrow();
+                let p2 = self.p2.borrow();
+                let diff = p1.position - p2.position;
+                let dist = diff.magnitude();
+                if dist > self.length * (1.0 + self.elasticity) {
+                    self.broken = true;
+                }
+                diff * ((self.length - dist) / dist) * 0.5
+            };
+
+            self.p1.borrow_mut().position += offset;
+            self.p2.borrow_mut().position -= offset;
+        }
+    }
+}
+
+/// Average time per update after warming up
+fn time_updates(mut update: impl FnMut()) -> Duration {
+    for _ in 0..WARMUP_STEPS {
+        update();
+    }
+
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        update();
+    }
+    start.elapsed() / STEPS
+}
+
+fn main() {
+    let mouse = Mouse::default();
+
+    for size in [50, 100, 200] {
+        // Unbreakable so that every run solves the same number of sticks
+        let mut cloth = Cloth::new(size, size, 5, 0, 0, f64::INFINITY);
+        let arrays = time_updates(|| cloth.update(DT, &mouse, Vector2::ZERO));
+
+        let mut cloth = reference::Cloth::new(size, size, 5, f64::INFINITY);
+        let shared = time_updates(|| cloth.update(DT, Vector2::ZERO));
+
+        println!(
+            "{size}x{size}: {:.1} us per update with parallel arrays, {:.1} us with Rc<RefCell> \
+             points and sticks, {:.2}x the throughput",
+            arrays.as_secs_f64() * 1e6,
+            shared.as_secs_f64() * 1e6,
+            shared.as_secs_f64() / arrays.as_secs_f64()
+        );
+    }
+}
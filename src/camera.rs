@@ -0,0 +1,72 @@
+use crate::math::Vector2;
+
+/// Pan and zoom applied when drawing, leaving the simulation in world space
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    // World position shown at the top left corner of the screen
+    pub offset: Vector2,
+    pub zoom: f64,
+}
+
+impl Camera {
+    pub fn new(offset: Vector2, zoom: f64) -> Self {
+        Camera { offset, zoom }
+    }
+
+    pub fn world_to_screen(&self, position: Vector2) -> Vector2 {
+        (position - self.offset) * self.zoom
+    }
+
+    pub fn screen_to_world(&self, position: Vector2) -> Vector2 {
+        position / self.zoom + self.offset
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::new(Vector2::ZERO, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Vector2, b: Vector2) -> bool {
+        a.distance(&b) < 1e-9
+    }
+
+    #[test]
+    fn world_to_screen() {
+        let point = Vector2::new(30.0, 40.0);
+        assert!(close(Camera::default().world_to_screen(point), point));
+
+        let panned = Camera::new(Vector2::new(10.0, -5.0), 1.0);
+        assert!(close(
+            panned.world_to_screen(point),
+            Vector2::new(20.0, 45.0)
+        ));
+
+        let zoomed = Camera::new(Vector2::new(10.0, 20.0), 2.0);
+        assert!(close(
+            zoomed.world_to_screen(point),
+            Vector2::new(40.0, 40.0)
+        ));
+        assert!(close(zoomed.world_to_screen(zoomed.offset), Vector2::ZERO));
+    }
+
+    #[test]
+    fn screen_to_world_undoes_world_to_screen() {
+        let camera = Camera::new(Vector2::new(-7.0, 3.5), 0.25);
+        for point in [
+            Vector2::ZERO,
+            Vector2::new(12.0, -8.0),
+            Vector2::new(400.0, 300.0),
+        ] {
+            assert!(close(
+                camera.screen_to_world(camera.world_to_screen(point)),
+                point
+            ));
+        }
+    }
+}
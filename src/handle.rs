@@ -0,0 +1,105 @@
+/// Stable reference to a point of a `Cloth` that stays valid while other points are removed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PointHandle(pub(crate) Key);
+
+/// Stable reference to a stick of a `Cloth` that stays valid while other sticks are removed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StickHandle(pub(crate) Key);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    slot: usize,
+    generation: u32,
+}
+
+#[derive(Clone)]
+struct Slot {
+    index: Option<usize>,
+    generation: u32,
+}
+
+/// Maps generational keys to indices of a densely packed array.
+///
+/// The array itself lives elsewhere, this only mirrors its pushes and removals so that
+/// a key resolves to the element's current index. Removing an element bumps its slot's
+/// generation, so a stale key never resolves to whatever reuses the slot later.
+#[derive(Clone, Default)]
+pub(crate) struct Slots {
+    slots: Vec<Slot>,
+    // Slot of each element in the dense array
+    slot_of: Vec<usize>,
+    free: Vec<usize>,
+}
+
+impl Slots {
+    /// Records an element pushed onto the end of the dense array
+    pub(crate) fn push(&mut self) -> Key {
+        let index = self.slot_of.len();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot].index = Some(index);
+                slot
+            }
+            None => {
+                self.slots.push(Slot {
+                    index: Some(index),
+                    generation: 0,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.slot_of.push(slot);
+        self.key(index).unwrap()
+    }
+
+    /// Records the removal of an element, shifting everything after it down by one
+    pub(crate) fn remove(&mut self, index: usize) {
+        let slot = self.slot_of.remove(index);
+        self.slots[slot].index = None;
+        self.slots[slot].generation += 1;
+        self.free.push(slot);
+
+        for &slot in &self.slot_of[index..] {
+            if let Some(i) = self.slots[slot].index.as_mut() {
+                *i -= 1;
+            }
+        }
+    }
+
+    pub(crate) fn key(&self, index: usize) -> Option<Key> {
+        let slot = *self.slot_of.get(index)?;
+        Some(Key {
+            slot,
+            generation: self.slots[slot].generation,
+        })
+    }
+
+    pub(crate) fn resolve(&self, key: Key) -> Option<usize> {
+        let slot = self.slots.get(key.slot)?;
+        if slot.generation != key.generation {
+            return None;
+        }
+        slot.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reused_slot_gets_new_generation() {
+        let mut slots = Slots::default();
+        let first = slots.push();
+        let second = slots.push();
+        slots.remove(0);
+        assert_eq!(slots.resolve(first), None);
+        assert_eq!(slots.resolve(second), Some(0));
+
+        let third = slots.push();
+        assert_eq!(third.slot, first.slot);
+        assert_eq!(third.generation, first.generation + 1);
+        assert_eq!(slots.resolve(third), Some(1));
+        assert_eq!(slots.resolve(first), None);
+    }
+}
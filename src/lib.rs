@@ -1,28 +1,275 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::mem;
+use std::path::Path;
+#[cfg(feature = "profiling")]
+use std::time::Instant;
 
-use crate::math::Vector2;
+use crate::camera::Camera;
+use crate::handle::{PointHandle, Slots, StickHandle};
+use crate::math::{
+    closest_point_on_segment, inverse_bilinear, point_segment_distance, segments_intersect, Vector2,
+};
+#[cfg(feature = "profiling")]
+use crate::profiling::FrameTimings;
+use crate::random::{layered_noise, Rng};
+use crate::snapshot::ClothSnapshot;
 use notan::draw::*;
 use notan::prelude::{Color, Mouse};
 
+pub mod camera;
+pub mod handle;
 pub mod math;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+mod random;
+pub mod snapshot;
 
-const GRAVITY: Vector2 = Vector2 { x: 0.0, y: 981.0 };
+/// Gravity cloths start with, pointing down the screen in pixels per second squared
+pub const DEFAULT_GRAVITY: Vector2 = Vector2 { x: 0.0, y: 981.0 };
 const CURSOR_RADIUS: f64 = 16.0;
 const FORCE_MULTIPLIER: f64 = 7000.0;
+const POINT_RADIUS: f64 = 1.0;
+const POINT_MASS: f64 = 1.0;
+// Distance within which gravity wells stop getting stronger, avoiding the singularity at their center
+const GRAVITY_WELL_MIN_DISTANCE: f64 = 10.0;
+// Number of tears remembered for `Cloth::undo_tear`
+const TEAR_HISTORY_LIMIT: usize = 256;
+// Highest smoothing factor allowed, above which smoothing overshoots and flips jitter instead of
+// damping it
+const MAX_SMOOTHING: f64 = 0.5;
+// Depth behind an obstacle's surface within which points are pushed back out
+const OBSTACLE_THICKNESS: f64 = 20.0;
+// Distance a point can move in a single step before the simulation is considered to have exploded
+const UNSTABLE_DISPLACEMENT: f64 = 1000.0;
+// Time step an initial velocity is converted to a verlet displacement with, in seconds
+const REFERENCE_DT: f64 = 1.0 / 60.0;
+// Layers of noise summed for turbulence
+const TURBULENCE_OCTAVES: u32 = 3;
+// Fraction of a stick's breaking strain it has to be strained by for a crack to run through it
+const CRACK_THRESHOLD: f64 = 0.5;
+// Largest mouse movement per update, in either axis, that drags an unbreakable cloth, which has no
+// elasticity to limit it by
+const UNBREAKABLE_DRAG_LIMIT: f64 = 7.0;
+// Strain `Cloth::draw_stress` reaches its most intense color at on an unbreakable cloth
+const UNBREAKABLE_STRESS_COLOR_MAX: f64 = 0.5;
 
 pub trait Render {
     fn render(&self, draw: &mut Draw);
 }
 
+/// How the sticks of a cloth are stroked
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    // Lengths of each drawn dash and of the gap after it
+    Dashed {
+        on: f32,
+        off: f32,
+    },
+}
+
+/// Which points of a cloth built with `Cloth::disc` are pinned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiscPin {
+    None,
+    Center,
+    Rim,
+}
+
+/// What `Cloth::draw` shows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Every stick as a line
+    #[default]
+    Wireframe,
+    /// Every intact cell of the grid as a filled quad
+    Filled,
+    /// Every stick colored by how strained it is
+    Stress,
+    /// Only the boundary of the cloth
+    Outline,
+    /// The filled grid with the boundary drawn on top, without the interior sticks
+    FilledOutline,
+}
+
+/// How points grabbed with the left mouse button follow the cursor
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GrabMode {
+    /// Pushes grabbed points along with the movement of the cursor, scaled by the drag strength
+    #[default]
+    Force,
+    /// Pulls grabbed points towards the cursor with a spring of `stiffness` per unit of distance,
+    /// slowed by `damping` per unit of speed. A damping of `2 * stiffness.sqrt()` is critically
+    /// damped, settling on the cursor as fast as possible without overshooting.
+    Spring { stiffness: f64, damping: f64 },
+}
+
+/// Copy of a point handed to the hook set with `Cloth::set_post_iteration`.
+/// Changes to the positions are written back to the cloth.
+#[derive(Clone, Copy, Debug)]
+pub struct PointView {
+    pub position: Vector2,
+    pub prev_position: Vector2,
+    pinned: bool,
+}
+
+impl PointView {
+    pub fn pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
+/// Custom constraint run on every point after each solver iteration
+pub type PostIterationHook = Box<dyn FnMut(&mut [PointView])>;
+
+/// Holds the post iteration hook. Closures can't be cloned, so a clone of the cloth starts without
+/// one rather than sharing it with the original.
+#[derive(Default)]
+struct PostIteration(Option<PostIterationHook>);
+
+impl Clone for PostIteration {
+    fn clone(&self) -> Self {
+        PostIteration(None)
+    }
+}
+
+#[derive(Clone)]
+enum Obstacle {
+    // Surface running through the points, solid on the right hand side of the direction of travel
+    // on screen, so an obstacle listed left to right is solid below the line
+    Polyline(Vec<Vector2>),
+}
+
+impl Obstacle {
+    /// Moves a point that is inside the obstacle back onto its surface
+    fn resolve(&self, position: &mut Vector2) {
+        match self {
+            Obstacle::Polyline(points) => {
+                let nearest = points.windows(2).min_by(|a, b| {
+                    let da = point_segment_distance(*position, a[0], a[1]);
+                    let db = point_segment_distance(*position, b[0], b[1]);
+                    da.total_cmp(&db)
+                });
+                let Some(&[a, b]) = nearest else {
+                    return;
+                };
+
+                let closest = closest_point_on_segment(*position, a, b);
+                let direction = b - a;
+                let outward = Vector2::new(direction.y, -direction.x).normalized();
+                let depth = -(*position - closest).dot(&outward);
+                if depth > 0.0 && depth < OBSTACLE_THICKNESS {
+                    *position = closest;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Cloth {
-    points: Vec<Rc<RefCell<Point>>>,
-    sticks: Vec<Rc<RefCell<Stick>>>,
+    // Point data is stored as parallel arrays indexed by point index
+    positions: Vec<Vector2>,
+    prev_positions: Vec<Vector2>,
+    initial_positions: Vec<Vector2>,
+    pinned: Vec<bool>,
+    // Where each pinned point is held, which starts at its initial position but moves with the pin
+    pin_targets: Vec<Vector2>,
+    // Multiplier on the gravity felt by each point
+    gravity_scales: Vec<f64>,
+    // Whether each point can be selected with the mouse
+    grabbable: Vec<bool>,
+    // Fraction of the way back to its initial position each point is pulled every update
+    pin_strengths: Vec<f64>,
+    // Anchor and stiffness of the spring holding each point near an anchor, if any
+    soft_pins: Vec<Option<(Vector2, f64)>>,
+    sticks: Vec<Stick>,
+    // Width and height of the grid the points were laid out in, if the layout is still intact
+    grid: Option<(usize, usize)>,
+    point_slots: Slots,
+    stick_slots: Slots,
     drag: f64,
+    drag_strength: f64,
+    grab_mode: GrabMode,
+    // Average force on the points grabbed during the last update
+    last_drag_force: Vector2,
     elasticity: f64,
+    over_relaxation: f64,
+    // Fraction of each stick's error corrected per update, regardless of the number of iterations
+    stiffness: f64,
+    // Fraction of the rest length a stick has to be stretched past its elasticity by to break
+    break_margin: f64,
+    // Consecutive updates a stick has to stay overstretched for before it breaks
+    break_delay: u32,
+    time_scale: f64,
+    gravity: Vector2,
+    // Seconds of simulated time gravity takes to ease in to full strength
+    gravity_rampup: f64,
+    wind: Vector2,
+    wind_shear: Vector2,
+    // Center and strength of every gravity well
+    gravity_wells: Vec<(Vector2, f64)>,
+    // Center, strength and radius of every vortex
+    vortices: Vec<(Vector2, f64, f64)>,
+    tethers: Vec<Tether>,
+    // Every obstacle along with the layers it is on
+    obstacles: Vec<(Obstacle, u32)>,
+    // Layers of obstacles the cloth collides with
+    collision_mask: u32,
+    // Opposite corners of a rectangle and the wind inside it, applied for the next update only
+    region_winds: Vec<((Vector2, Vector2), Vector2)>,
+    // Swept segment, radius and push strength of objects moving through the cloth
+    sweeps: Vec<((Vector2, Vector2), f64, f64)>,
+    // Strength of the turbulence and the distance over which it varies
+    turbulence: Option<(f64, f64)>,
+    // Whether the middle and right mouse buttons were held during the previous update
+    prev_middle_down: bool,
+    prev_right_down: bool,
+    // Whether holding the right mouse button keeps cutting instead of cutting once per click
+    continuous_cut: bool,
+    // Whether the pinned points move along with the mouse
+    anchors_follow_mouse: bool,
+    // Maximum number of cracks advanced per update, or None if cracks don't propagate
+    crack_propagation: Option<usize>,
+    // Points at the end of a recent tear that a crack can continue from
+    crack_tips: Vec<usize>,
+    // Whether any stick has broken since broken sticks were last removed, so the removal can be
+    // skipped while the cloth is intact
+    any_broken: bool,
+    // Most recently torn sticks, newest last
+    tear_history: VecDeque<Stick>,
+    // Seconds a burning stick takes to ignite its neighbors, and to burn through
+    burn_spread_time: f64,
+    burn_out_time: f64,
+    smoothing: f64,
+    // Most stick solves per update, and the relative stick length error at which to stop early
+    max_iterations: usize,
+    convergence_tolerance: f64,
+    last_iteration_count: usize,
+    // Strain drawn with the most intense color when drawing by stress
+    stress_color_max: f64,
+    line_style: LineStyle,
+    render_mode: RenderMode,
+    step_count: u64,
+    // Whether updates are skipped entirely
+    frozen: bool,
+    // Whether the cloth resets itself when the simulation blows up
+    auto_recover: bool,
+    rng: Rng,
+    seed: u64,
+    // Simulated seconds since the cloth was created or reset
+    time: f64,
+    post_iteration: PostIteration,
+    #[cfg(feature = "profiling")]
+    last_timings: FrameTimings,
 }
 
 impl Cloth {
+    /// Builds a grid of points with half of the top row pinned. `elasticity` is the fraction of its
+    /// length a stick can stretch by before breaking, `f64::INFINITY` makes the cloth unbreakable.
     pub fn new(
         width: i32,
         height: i32,
@@ -31,247 +278,4574 @@ impl Cloth {
         start_y: i32,
         elasticity: f64,
     ) -> Self {
-        let mut points = Vec::new();
-        let mut sticks = Vec::new();
+        Cloth::build_grid(width, height, spacing, start_x, start_y, elasticity, true)
+    }
+
+    /// Builds the same grid as `Cloth::new` but without pinning any points, so the whole cloth falls
+    pub fn unpinned(
+        width: i32,
+        height: i32,
+        spacing: i32,
+        start_x: i32,
+        start_y: i32,
+        elasticity: f64,
+    ) -> Self {
+        Cloth::build_grid(width, height, spacing, start_x, start_y, elasticity, false)
+    }
+
+    fn build_grid(
+        width: i32,
+        height: i32,
+        spacing: i32,
+        start_x: i32,
+        start_y: i32,
+        elasticity: f64,
+        pin_top: bool,
+    ) -> Self {
+        let mut cloth = Cloth::empty(elasticity);
+        cloth.grid = Some((width as usize, height as usize));
 
         for y in 0..height {
             for x in 0..width {
-                let point = Rc::new(RefCell::new(Point::new(Vector2::new(
+                let index = cloth.add_point(Vector2::new(
                     (start_x + x * spacing) as f64,
                     (start_y + y * spacing) as f64,
-                ))));
+                ));
 
                 // Provided that the point is not the first point in the row, create a stick to the left
                 if x != 0 {
-                    let left_point = points.last().unwrap();
-                    let stick = Rc::new(RefCell::new(Stick::new(
-                        Rc::clone(&point),
-                        Rc::clone(left_point),
-                        spacing as f64,
-                        elasticity,
-                    )));
-                    left_point.borrow_mut().add_stick(Rc::clone(&stick), 0);
-                    point.borrow_mut().add_stick(Rc::clone(&stick), 0);
-
-                    sticks.push(stick);
+                    cloth.add_stick(Stick::new(index, index - 1, spacing as f64, elasticity));
                 }
 
                 // Provided that the point is not the first point in the column, create a stick to the top
                 if y != 0 {
-                    let up_point = points.get((x + (y - 1) * width) as usize).unwrap();
-                    let stick = Rc::new(RefCell::new(Stick::new(
-                        Rc::clone(&point),
-                        Rc::clone(up_point),
-                        spacing as f64,
-                        elasticity,
-                    )));
-
-                    up_point.borrow_mut().add_stick(Rc::clone(&stick), 1);
-                    point.borrow_mut().add_stick(Rc::clone(&stick), 1);
-
-                    sticks.push(stick);
+                    let up_index = (x + (y - 1) * width) as usize;
+                    cloth.add_stick(Stick::new(index, up_index, spacing as f64, elasticity));
                 }
 
                 // Pin half of the top points so that the cloth doesn't fall off the screen
+                if pin_top && y == 0 && x % 2 == 0 {
+                    cloth.pinned[index] = true;
+                }
+            }
+        }
+        cloth
+    }
+
+    /// Builds a grid like `Cloth::new` whose rows are centered on `center_x` and spaced
+    /// `row_spacing` apart, but with the points of each row spaced `spacing_fn(row)` apart, so rows
+    /// that widen towards the bottom make a trapezoid for shapes like skirts. Half of the top row
+    /// is pinned.
+    pub fn trapezoid(
+        width: i32,
+        height: i32,
+        row_spacing: i32,
+        center_x: i32,
+        start_y: i32,
+        elasticity: f64,
+        spacing_fn: impl Fn(i32) -> f64,
+    ) -> Self {
+        let mut cloth = Cloth::empty(elasticity);
+        cloth.grid = Some((width as usize, height as usize));
+
+        for y in 0..height {
+            let spacing = spacing_fn(y);
+            for x in 0..width {
+                let offset = (x as f64 - (width - 1) as f64 / 2.0) * spacing;
+                let index = cloth.add_point(Vector2::new(
+                    center_x as f64 + offset,
+                    (start_y + y * row_spacing) as f64,
+                ));
+
+                if x != 0 {
+                    cloth.add_stick(Stick::new(index, index - 1, spacing, elasticity));
+                }
+
+                // Rows have different spacings, so sticks to the row above slant outwards
+                if y != 0 {
+                    let up_index = (x + (y - 1) * width) as usize;
+                    let length = cloth.positions[index].distance(&cloth.positions[up_index]);
+                    cloth.add_stick(Stick::new(index, up_index, length, elasticity));
+                }
+
                 if y == 0 && x % 2 == 0 {
-                    point.borrow_mut().pin();
+                    cloth.pinned[index] = true;
                 }
+            }
+        }
+        cloth
+    }
+
+    /// Builds a round cloth of `rings` concentric rings of `segments` points each around a center
+    /// point, with sticks running outwards between rings and around every ring. The center is
+    /// point 0, followed by the rings from the inside out, each starting on the right of the center.
+    pub fn disc(
+        rings: usize,
+        segments: usize,
+        radius: f64,
+        center: Vector2,
+        elasticity: f64,
+        pin: DiscPin,
+    ) -> Self {
+        assert!(segments >= 3, "a disc needs at least 3 segments");
+        let mut cloth = Cloth::empty(elasticity);
+
+        let center_index = cloth.add_point(center);
+        cloth.pinned[center_index] = pin == DiscPin::Center;
+
+        let index_of = |ring: usize, segment: usize| 1 + (ring - 1) * segments + segment % segments;
+        for ring in 1..=rings {
+            let ring_radius = radius * ring as f64 / rings as f64;
+            for segment in 0..segments {
+                let angle = std::f64::consts::TAU * segment as f64 / segments as f64;
+                let index =
+                    cloth.add_point(center + Vector2::new(angle.cos(), angle.sin()) * ring_radius);
+                cloth.pinned[index] = pin == DiscPin::Rim && ring == rings;
+            }
+        }
+
+        let connect = |cloth: &mut Cloth, a: usize, b: usize| {
+            let length = cloth.positions[a].distance(&cloth.positions[b]);
+            cloth.add_stick(Stick::new(a, b, length, elasticity));
+        };
+        for ring in 1..=rings {
+            for segment in 0..segments {
+                let index = index_of(ring, segment);
+                let inward = if ring == 1 {
+                    center_index
+                } else {
+                    index_of(ring - 1, segment)
+                };
+                connect(&mut cloth, index, inward);
+                connect(&mut cloth, index, index_of(ring, segment + 1));
+            }
+        }
+        cloth
+    }
+
+    /// Builds a cloth from arbitrary points, given as `(position, pinned)`, and sticks, given as
+    /// `(point_a, point_b, elasticity)`. The rest length of every stick is the current distance
+    /// between its points.
+    pub fn from_parts(points: &[(Vector2, bool)], sticks: &[(usize, usize, f64)]) -> Self {
+        // The cloth wide elasticity limits the mouse drag, so go with the stretchiest stick. With no
+        // stretchy stick to go by the drag falls back to the limit of an unbreakable cloth.
+        let elasticity = sticks.iter().fold(0.0, |max, stick| stick.2.max(max));
+        let elasticity = if elasticity > 0.0 {
+            elasticity
+        } else {
+            f64::INFINITY
+        };
+        let mut cloth = Cloth::empty(elasticity);
+
+        for &(position, pinned) in points {
+            let index = cloth.add_point(position);
+            cloth.pinned[index] = pinned;
+        }
+        for &(a, b, elasticity) in sticks {
+            let length = cloth.positions[a].distance(&cloth.positions[b]);
+            cloth.add_stick(Stick::new(a, b, length, elasticity));
+        }
+        cloth
+    }
 
-                points.push(point);
+    /// Makes every stick only resist stretching, going slack instead of pushing its points apart
+    /// when compressed, like a rope
+    pub fn with_one_way_sticks(mut self, one_way: bool) -> Self {
+        for stick in &mut self.sticks {
+            stick.one_way = one_way;
+        }
+        self
+    }
+
+    /// Makes sticks only break once stretched past their elasticity by an extra `margin` fraction of
+    /// their rest length, so a stick hovering right at the threshold doesn't break inconsistently
+    pub fn with_break_margin(mut self, margin: f64) -> Self {
+        self.break_margin = margin;
+        self
+    }
+
+    /// Makes sticks only break once they have been overstretched for more than `frames` updates in
+    /// a row, so a stick that is only stretched past its limit for a moment by the solver survives
+    pub fn with_break_delay(mut self, frames: u32) -> Self {
+        self.break_delay = frames;
+        self
+    }
+
+    /// Starts every free point moving at `velocity`, in distance per second at 60 updates per
+    /// second, for cloths that are thrown rather than dropped
+    pub fn with_initial_velocity(mut self, velocity: Vector2) -> Self {
+        for i in 0..self.positions.len() {
+            if !self.pinned[i] {
+                self.prev_positions[i] = self.positions[i] - velocity * REFERENCE_DT;
             }
         }
+        self
+    }
+
+    /// Eases gravity in from nothing to full strength over the first `seconds` of simulated time,
+    /// letting stiff cloths settle gently instead of snapping on the first frames
+    pub fn with_gravity_rampup(mut self, seconds: f64) -> Self {
+        self.gravity_rampup = seconds;
+        self
+    }
+
+    /// Seeds the random number generator behind randomized effects like `jitter` and turbulence,
+    /// which otherwise always starts from the same seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+        self.seed = seed;
+        self
+    }
+
+    fn empty(elasticity: f64) -> Self {
         Cloth {
-            points,
-            sticks,
+            positions: Vec::new(),
+            prev_positions: Vec::new(),
+            initial_positions: Vec::new(),
+            pin_targets: Vec::new(),
+            pinned: Vec::new(),
+            gravity_scales: Vec::new(),
+            grabbable: Vec::new(),
+            pin_strengths: Vec::new(),
+            soft_pins: Vec::new(),
+            sticks: Vec::new(),
+            grid: None,
+            point_slots: Slots::default(),
+            stick_slots: Slots::default(),
             drag: 0.05,
+            drag_strength: FORCE_MULTIPLIER,
+            grab_mode: GrabMode::Force,
+            last_drag_force: Vector2::ZERO,
             elasticity,
+            over_relaxation: 1.0,
+            stiffness: 1.0,
+            break_margin: 0.0,
+            break_delay: 0,
+            time_scale: 1.0,
+            gravity: DEFAULT_GRAVITY,
+            gravity_rampup: 0.0,
+            wind: Vector2::ZERO,
+            wind_shear: Vector2::ZERO,
+            gravity_wells: Vec::new(),
+            vortices: Vec::new(),
+            tethers: Vec::new(),
+            region_winds: Vec::new(),
+            sweeps: Vec::new(),
+            turbulence: None,
+            obstacles: Vec::new(),
+            collision_mask: u32::MAX,
+            prev_middle_down: false,
+            prev_right_down: false,
+            continuous_cut: false,
+            anchors_follow_mouse: false,
+            crack_propagation: None,
+            crack_tips: Vec::new(),
+            any_broken: false,
+            tear_history: VecDeque::new(),
+            burn_spread_time: 0.1,
+            burn_out_time: 0.5,
+            smoothing: 0.0,
+            max_iterations: 1,
+            convergence_tolerance: 0.0,
+            last_iteration_count: 0,
+            stress_color_max: if elasticity.is_finite() {
+                elasticity
+            } else {
+                UNBREAKABLE_STRESS_COLOR_MAX
+            },
+            line_style: LineStyle::Solid,
+            render_mode: RenderMode::Wireframe,
+            step_count: 0,
+            frozen: false,
+            auto_recover: false,
+            rng: Rng::new(0),
+            seed: 0,
+            time: 0.0,
+            post_iteration: PostIteration::default(),
+            #[cfg(feature = "profiling")]
+            last_timings: FrameTimings::default(),
         }
     }
 
-    pub fn update(&mut self, dt: f64, mouse: &Mouse, prev_mouse_position: Vector2) {
-        for point in &self.points {
-            let mut point = point.borrow_mut();
+    fn add_point(&mut self, position: Vector2) -> usize {
+        self.positions.push(position);
+        self.prev_positions.push(position);
+        self.initial_positions.push(position);
+        self.pin_targets.push(position);
+        self.pinned.push(false);
+        self.gravity_scales.push(1.0);
+        self.grabbable.push(true);
+        self.pin_strengths.push(0.0);
+        self.soft_pins.push(None);
+        self.point_slots.push();
+        self.positions.len() - 1
+    }
 
-            // Check if the point is within the mouse's selection radius
-            // Uses the square of the magnitude instead of distance since sqrt is expensive
-            let dist_sq = (point.position - Vector2::from(mouse.position())).magnitude_squared();
-            let selected = dist_sq <= CURSOR_RADIUS * CURSOR_RADIUS;
+    fn add_stick(&mut self, stick: Stick) -> usize {
+        self.sticks.push(stick);
+        self.stick_slots.push();
+        self.sticks.len() - 1
+    }
 
-            let mut force = GRAVITY;
+    /// Adds an unconnected point, possibly reusing the slot of a removed point
+    pub fn insert_point(&mut self, position: Vector2, pinned: bool) -> PointHandle {
+        let index = self.add_point(position);
+        self.pinned[index] = pinned;
+        // The point sits outside the grid the other points were laid out in
+        self.grid = None;
+        self.point_handle(index).unwrap()
+    }
 
-            // Apply force from mouse dragging
-            if selected {
-                if mouse.left_is_down() {
-                    let diff = Vector2::from(mouse.position()) - prev_mouse_position;
-                    let clamped = Vector2::new(
-                        diff.x.clamp(-self.elasticity, self.elasticity),
-                        diff.y.clamp(-self.elasticity, self.elasticity),
-                    );
-                    force += clamped * FORCE_MULTIPLIER
-                } else if mouse.right_is_down() {
-                    point.break_sticks();
-                }
-            }
+    pub fn len_points(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Number of sticks, including any torn since the last update that have yet to be removed
+    pub fn len_sticks(&self) -> usize {
+        self.sticks.len()
+    }
+
+    pub fn point_handle(&self, index: usize) -> Option<PointHandle> {
+        self.point_slots.key(index).map(PointHandle)
+    }
+
+    /// Returns the current index of the point, or `None` if it has been removed
+    pub fn point_index(&self, handle: PointHandle) -> Option<usize> {
+        self.point_slots.resolve(handle.0)
+    }
+
+    pub fn stick_handle(&self, index: usize) -> Option<StickHandle> {
+        self.stick_slots.key(index).map(StickHandle)
+    }
+
+    /// Returns the current index of the stick, or `None` if it has been removed
+    pub fn stick_index(&self, handle: StickHandle) -> Option<usize> {
+        self.stick_slots.resolve(handle.0)
+    }
+
+    pub fn position(&self, handle: PointHandle) -> Option<Vector2> {
+        self.point_index(handle).map(|i| self.positions[i])
+    }
+
+    /// Appends the points and sticks of another cloth to this one without connecting them.
+    ///
+    /// Handles into `other` do not carry over, new ones are issued for the appended elements.
+    pub fn merge(&mut self, other: Cloth) {
+        let offset = self.positions.len();
 
-            point.update(dt, self.drag, force, selected);
+        for _ in 0..other.positions.len() {
+            self.point_slots.push();
+        }
+        for _ in 0..other.sticks.len() {
+            self.stick_slots.push();
         }
 
-        // Apply stick constraints and remove broken sticks
+        // The appended points don't continue the grid of this cloth
+        self.grid = None;
+
+        self.positions.extend(other.positions);
+        self.prev_positions.extend(other.prev_positions);
+        self.initial_positions.extend(other.initial_positions);
+        self.pin_targets.extend(other.pin_targets);
+        self.pinned.extend(other.pinned);
+        self.any_broken |= other.any_broken;
+        self.gravity_scales.extend(other.gravity_scales);
+        self.grabbable.extend(other.grabbable);
+        self.pin_strengths.extend(other.pin_strengths);
+        self.soft_pins.extend(other.soft_pins);
+        self.tethers
+            .extend(other.tethers.into_iter().map(|mut tether| {
+                tether.point += offset;
+                tether
+            }));
+        self.sticks
+            .extend(other.sticks.into_iter().map(|mut stick| {
+                stick.p1 += offset;
+                stick.p2 += offset;
+                stick
+            }));
+    }
+
+    /// Removes a point along with every stick connected to it, returning its last position.
+    ///
+    /// Like `Vec::remove`, every point after the removed one shifts down by one index, so raw
+    /// indices held for those points must be decremented. Handles stay valid, except for the
+    /// removed point's own handle which resolves to `None` from now on, even once its slot is reused.
+    pub fn remove_point(&mut self, handle: PointHandle) -> Option<Vector2> {
+        let index = self.point_index(handle)?;
+
         let mut to_remove = Vec::new();
-        for (i, stick) in self.sticks.iter().enumerate() {
-            let mut stick = stick.borrow_mut();
+        for (i, stick) in self.sticks.iter_mut().enumerate() {
+            if stick.p1 == index || stick.p2 == index {
+                stick.broken = true;
+            }
             if stick.broken {
                 to_remove.push(i);
             }
+        }
+        self.any_broken = false;
+        for stick in self.remove_sticks(to_remove) {
+            // Sticks that were already torn can still be restored later
+            if stick.p1 != index && stick.p2 != index {
+                self.record_tear(stick);
+            }
+        }
+        self.tear_history
+            .retain(|stick| stick.p1 != index && stick.p2 != index);
 
-            stick.update();
+        // Remap the remaining sticks to account for the shifted points
+        for stick in self.sticks.iter_mut().chain(&mut self.tear_history) {
+            if stick.p1 > index {
+                stick.p1 -= 1;
+            }
+            if stick.p2 > index {
+                stick.p2 -= 1;
+            }
         }
-        self.remove_sticks(to_remove);
-    }
 
-    fn remove_sticks(&mut self, indices: Vec<usize>) {
-        for i in indices.iter().rev() {
-            self.sticks.remove(*i);
+        self.crack_tips.retain(|&tip| tip != index);
+        for tip in &mut self.crack_tips {
+            if *tip > index {
+                *tip -= 1;
+            }
+        }
+        self.tethers.retain(|tether| tether.point != index);
+        for tether in &mut self.tethers {
+            if tether.point > index {
+                tether.point -= 1;
+            }
         }
+
+        // Shifting the points breaks the mapping from grid coordinates to indices
+        self.grid = None;
+
+        self.point_slots.remove(index);
+        self.prev_positions.remove(index);
+        self.initial_positions.remove(index);
+        self.pin_targets.remove(index);
+        self.pinned.remove(index);
+        self.gravity_scales.remove(index);
+        self.grabbable.remove(index);
+        self.pin_strengths.remove(index);
+        self.soft_pins.remove(index);
+        Some(self.positions.remove(index))
     }
 
-    pub fn draw(&self, draw: &mut Draw) {
-        for stick in &self.sticks {
-            stick.borrow().render(draw);
-        }
+    /// Sets how strongly dragging with the mouse pulls on the cloth
+    pub fn set_drag_strength(&mut self, strength: f64) {
+        self.drag_strength = strength;
     }
-}
 
-struct Point {
-    position: Vector2,
-    prev_position: Vector2,
-    initial_position: Vector2,
-    sticks: [Option<Rc<RefCell<Stick>>>; 2],
-    pinned: bool,
-}
+    /// Sets how grabbed points follow the cursor
+    pub fn set_grab_mode(&mut self, mode: GrabMode) {
+        self.grab_mode = mode;
+    }
 
-impl Point {
-    fn new(position: Vector2) -> Self {
-        Point {
-            position,
-            prev_position: position,
-            initial_position: position,
-            sticks: [None, None],
-            pinned: false,
-        }
+    /// Force the mouse applied to each grabbed point during the last update, averaged over the
+    /// grabbed points, or zero if nothing was being dragged
+    pub fn last_drag_force(&self) -> Vector2 {
+        self.last_drag_force
+    }
+
+    /// Sets the factor every stick correction is multiplied by.
+    ///
+    /// Values in `(1.0, 2.0)` over-correct each stick which makes the cloth converge to its rest
+    /// lengths faster, while values approaching or above 2.0 make the solver diverge.
+    pub fn set_over_relaxation(&mut self, factor: f64) {
+        self.over_relaxation = factor;
+    }
+
+    pub fn elasticity(&self) -> f64 {
+        self.elasticity
+    }
+
+    pub fn drag(&self) -> f64 {
+        self.drag
     }
 
-    fn break_sticks(&mut self) {
-        if let Some(stick) = &self.sticks[0] {
-            stick.borrow_mut().broken = true;
-            self.sticks[0] = None;
+    pub fn gravity(&self) -> Vector2 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vector2) {
+        self.gravity = gravity;
+    }
+
+    /// Sets how fast simulated time passes compared to the `dt` given to `update`,
+    /// 0.5 running at half speed and 2.0 at double speed
+    pub fn set_time_scale(&mut self, scale: f64) {
+        self.time_scale = scale;
+    }
+
+    /// Sets how far each point's velocity is blended towards the average velocity of its neighbors
+    /// after the sticks are solved, which damps jitter while leaving the cloth's shape and overall
+    /// motion alone. Clamped to `[0, 0.5]` since stronger smoothing overshoots the average.
+    ///
+    /// Velocities are smoothed rather than positions, because pulling points towards their
+    /// neighbors shrinks the cloth at its edges and the sticks pushing it back out adds jitter.
+    pub fn set_smoothing(&mut self, factor: f64) {
+        self.smoothing = factor.clamp(0.0, MAX_SMOOTHING);
+    }
+
+    fn smooth(&mut self) {
+        let velocities: Vec<Vector2> = (0..self.positions.len())
+            .map(|i| self.velocity(i))
+            .collect();
+        let mut sums = vec![Vector2::ZERO; self.positions.len()];
+        let mut counts = vec![0; self.positions.len()];
+        for stick in &self.sticks {
+            sums[stick.p1] += velocities[stick.p2];
+            sums[stick.p2] += velocities[stick.p1];
+            counts[stick.p1] += 1;
+            counts[stick.p2] += 1;
         }
-        if let Some(stick) = &self.sticks[1] {
-            stick.borrow_mut().broken = true;
-            self.sticks[1] = None;
+
+        for (i, prev_position) in self.prev_positions.iter_mut().enumerate() {
+            if self.pinned[i] || counts[i] == 0 {
+                continue;
+            }
+            let average = sums[i] / counts[i] as f64;
+            let velocity = velocities[i] + (average - velocities[i]) * self.smoothing;
+            *prev_position = self.positions[i] - velocity;
         }
     }
 
-    fn add_stick(&mut self, stick: Rc<RefCell<Stick>>, add_index: usize) {
-        self.sticks[add_index] = Some(stick);
+    /// Solves the sticks up to `max` times every update, stopping early once no intact stick is off
+    /// its rest length by more than `tolerance` times that length. More iterations make the cloth
+    /// stiffer at the cost of speed, unless its stiffness is turned down with `set_stiffness`, in
+    /// which case they only make it more accurate. A tolerance of 0 always runs every iteration, as
+    /// does a stiffness below 1.
+    ///
+    /// Defaults to a single iteration. Sticks only break during the first.
+    pub fn set_solver_iterations(&mut self, max: usize, tolerance: f64) {
+        self.max_iterations = max.max(1);
+        self.convergence_tolerance = tolerance;
+    }
+
+    /// Sets how stiff the sticks are, from 0 for not holding together at all to 1 for correcting
+    /// them fully. The stiffness is spread over the solver iterations so that the cloth feels the
+    /// same however many iterations are run. Below 1 the solver runs every iteration rather than
+    /// stopping early once the sticks are within the convergence tolerance. Defaults to 1.
+    pub fn set_stiffness(&mut self, stiffness: f64) {
+        self.stiffness = stiffness.clamp(0.0, 1.0);
+    }
+
+    /// Correction factor for a single solver iteration that adds up to the cloth's stiffness over
+    /// the maximum number of iterations, as in position based dynamics
+    fn iteration_stiffness(&self) -> f64 {
+        1.0 - (1.0 - self.stiffness).powf(1.0 / self.max_iterations as f64)
+    }
+
+    /// Number of times the sticks were solved during the most recent update
+    pub fn last_iteration_count(&self) -> usize {
+        self.last_iteration_count
     }
 
-    fn pin(&mut self) {
-        self.pinned = true;
+    /// Whether every intact stick is within the convergence tolerance of its rest length
+    fn converged(&self) -> bool {
+        self.convergence_tolerance > 0.0
+            && self
+                .sticks
+                .iter()
+                .filter(|stick| !stick.broken)
+                .all(|stick| {
+                    let dist = self.positions[stick.p1].distance(&self.positions[stick.p2]);
+                    (dist - stick.length).abs() <= self.convergence_tolerance * stick.length
+                })
     }
 
-    fn update(&mut self, dt: f64, drag: f64, acceleration: Vector2, selected: bool) {
-        // Highlight
-        for stick in self.sticks.iter().flatten() {
-            stick.borrow_mut().selected = selected;
+    /// Solves the sticks `iterations` times without advancing time, settling the cloth towards
+    /// its rest shape. Velocities are preserved, pinned points stay put and no sticks break.
+    pub fn relax(&mut self, iterations: usize) {
+        let start = self.positions.clone();
+
+        for _ in 0..iterations {
+            for stick in self.sticks.iter().filter(|stick| !stick.broken) {
+                stick.solve(&mut self.positions, self.over_relaxation);
+            }
+            self.solve_tethers();
+            self.resolve_collisions();
+            self.snap_pinned_points();
+            self.run_post_iteration();
+        }
+
+        // Shift the previous positions along so the verlet velocity stays the same
+        for (i, prev_position) in self.prev_positions.iter_mut().enumerate() {
+            *prev_position += self.positions[i] - start[i];
         }
-        if self.pinned {
-            self.position = self.initial_position;
+    }
+
+    /// Sets a hook that runs after every iteration of the stick solver, once all sticks have been
+    /// solved and before torn sticks are removed, allowing custom constraints to be applied on top.
+    /// Clones of the cloth start without a hook, so set it again on a clone that needs it.
+    pub fn set_post_iteration(&mut self, hook: PostIterationHook) {
+        self.post_iteration = PostIteration(Some(hook));
+    }
+
+    fn run_post_iteration(&mut self) {
+        let Some(hook) = &mut self.post_iteration.0 else {
             return;
+        };
+
+        let mut points: Vec<PointView> = (0..self.positions.len())
+            .map(|i| PointView {
+                position: self.positions[i],
+                prev_position: self.prev_positions[i],
+                pinned: self.pinned[i],
+            })
+            .collect();
+        hook(&mut points);
+
+        for (i, point) in points.iter().enumerate() {
+            self.positions[i] = point.position;
+            self.prev_positions[i] = point.prev_position;
         }
+    }
 
-        // Solve for new position using verlet integration
-        let new_position = self.position
-            + (self.position - self.prev_position) * (1.0 - drag)
-            + acceleration * (1.0 - drag) * dt * dt;
-        self.prev_position = self.position;
-        self.position = new_position;
+    /// Sets the rest length of every stick to its current length, making the current shape the
+    /// one the cloth relaxes towards
+    pub fn bake_rest_lengths(&mut self) {
+        for stick in &mut self.sticks {
+            stick.length = self.positions[stick.p1].distance(&self.positions[stick.p2]);
+        }
     }
-}
 
-impl Render for Point {
-    fn render(&self, draw: &mut Draw) {
-        draw.circle(1.0)
-            .position(self.position.x as f32, self.position.y as f32);
+    /// Moves every pinned point back onto its pin
+    fn snap_pinned_points(&mut self) {
+        for (i, pinned) in self.pinned.iter().enumerate() {
+            if *pinned {
+                self.positions[i] = self.pin_targets[i];
+            }
+        }
     }
-}
 
-impl Default for Point {
-    fn default() -> Self {
-        Self::new(Vector2::ZERO)
+    /// Skews every point by `x' = x + kx * y` and `y' = y + ky * x`.
+    ///
+    /// Rest lengths are left untouched so the cloth springs back from the deformation.
+    pub fn shear(&mut self, kx: f64, ky: f64) {
+        self.transform(|p| Vector2::new(p.x + kx * p.y, p.y + ky * p.x));
     }
-}
 
-struct Stick {
-    p1: Rc<RefCell<Point>>,
-    p2: Rc<RefCell<Point>>,
-    length: f64,
-    elasticity: f64,
-    selected: bool,
-    broken: bool,
-}
+    /// Applies a transform to the current, previous and initial positions and the pin of every point
+    fn transform(&mut self, f: impl Fn(Vector2) -> Vector2) {
+        for position in self
+            .positions
+            .iter_mut()
+            .chain(&mut self.prev_positions)
+            .chain(&mut self.initial_positions)
+            .chain(&mut self.pin_targets)
+        {
+            *position = f(*position);
+        }
+    }
 
-impl Stick {
-    fn new(p1: Rc<RefCell<Point>>, p2: Rc<RefCell<Point>>, length: f64, elasticity: f64) -> Self {
-        // Elasticity should be greater than 0 since its the percent of the length
-        // that the stick can stretch before breaking.
+    /// Damps the sticks, removing `damping` of the speed their points move apart or together along
+    /// the stick every update so stretchy cloth stops bouncing sooner. 0 disables damping and 1
+    /// stops the motion along every stick instantly.
+    pub fn set_stick_damping(&mut self, damping: f64) {
+        let damping = damping.clamp(0.0, 1.0);
+        for stick in &mut self.sticks {
+            stick.damping = damping;
+        }
+    }
+
+    /// Sets the elasticity of every stick whose midpoint lies inside the box between `min` and `max`,
+    /// so parts of the cloth can be made stiffer or tear more easily than the rest
+    pub fn set_region_elasticity(&mut self, min: Vector2, max: Vector2, elasticity: f64) {
         assert!(elasticity >= 0.0);
-        Stick {
-            p1,
-            p2,
-            length,
-            elasticity,
-            selected: false,
-            broken: false,
+
+        for stick in &mut self.sticks {
+            let midpoint = (self.positions[stick.p1] + self.positions[stick.p2]) / 2.0;
+            let inside_x = midpoint.x >= min.x && midpoint.x <= max.x;
+            let inside_y = midpoint.y >= min.y && midpoint.y <= max.y;
+            if inside_x && inside_y {
+                stick.elasticity = elasticity;
+            }
+        }
+    }
+
+    /// Multiplies the constraint correction of sticks within `radius` sticks of a pinned point
+    /// by `stiffness`, reducing stretching and tearing around the anchors.
+    ///
+    /// Values in `(1.0, 2.0)` stiffen the sticks, `1.0` restores the default. The affected sticks are
+    /// chosen from the topology at the time of the call.
+    pub fn set_anchor_reinforcement(&mut self, radius: usize, stiffness: f64) {
+        // Breadth first search outwards from every pinned point at once
+        let adjacency = self.adjacency();
+        let mut depth = vec![usize::MAX; self.positions.len()];
+        let mut queue = VecDeque::new();
+        for (i, pinned) in self.pinned.iter().enumerate() {
+            if *pinned {
+                depth[i] = 0;
+                queue.push_back(i);
+            }
+        }
+        while let Some(i) = queue.pop_front() {
+            for &neighbor in &adjacency[i] {
+                if depth[neighbor] == usize::MAX {
+                    depth[neighbor] = depth[i] + 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        for stick in &mut self.sticks {
+            let near_anchor = depth[stick.p1].min(depth[stick.p2]) < radius;
+            stick.stiffness = if near_anchor { stiffness } else { 1.0 };
         }
     }
 
-    fn update(&mut self) {
-        let offset = {
-            let p1 = self.p1.borrow();
-            let p2 = self.p2.borrow();
+    /// Bilinearly interpolates the current position at a fractional coordinate of the grid, where
+    /// `(0, 0)` is the first point and `(1, 1)` the last.
+    ///
+    /// Returns `None` if the coordinate is out of range, the grid layout was lost by adding or
+    /// removing points or the surrounding quad has been torn. `sample_grid` takes coordinates in
+    /// points instead.
+    pub fn sample(&self, u: f64, v: f64) -> Option<Vector2> {
+        let (width, height) = self.grid?;
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) || width == 0 || height == 0 {
+            return None;
+        }
 
-            let diff = p1.position - p2.position;
-            let dist = diff.magnitude();
+        let (position, edges) = self.interpolate(
+            width,
+            height,
+            u * (width - 1) as f64,
+            v * (height - 1) as f64,
+        );
+        let edges: Vec<(usize, usize)> = edges.into_iter().filter(|&(a, b)| a != b).collect();
+        if !self.all_connected(&edges) {
+            return None;
+        }
+        Some(position)
+    }
 
-            // Break the stick if it stretches too much
-            if dist > self.length * (1.0 + self.elasticity) {
-                self.broken = true;
+    /// Bilinearly interpolates the current position at a fractional grid coordinate, where `(0, 0)`
+    /// is the first point and `(width - 1, height - 1)` the last. Out of range coordinates are
+    /// clamped, and tears are interpolated across.
+    ///
+    /// Panics if the grid layout was lost by adding or removing points.
+    pub fn sample_grid(&self, u: f64, v: f64) -> Vector2 {
+        let (width, height) = self.grid.expect("cloth no longer has a grid layout");
+        let u = u.clamp(0.0, width.saturating_sub(1) as f64);
+        let v = v.clamp(0.0, height.saturating_sub(1) as f64);
+        self.interpolate(width, height, u, v).0
+    }
+
+    /// Position at an in-range grid coordinate, along with the four edges of the quad it lies in
+    fn interpolate(
+        &self,
+        width: usize,
+        height: usize,
+        u: f64,
+        v: f64,
+    ) -> (Vector2, [(usize, usize); 4]) {
+        // Top left corner of the surrounding quad, kept off the last row and column
+        let x = (u.floor() as usize).min(width.saturating_sub(2));
+        let y = (v.floor() as usize).min(height.saturating_sub(2));
+        let x1 = (x + 1).min(width - 1);
+        let y1 = (y + 1).min(height - 1);
+        let (tx, ty) = (u - x as f64, v - y as f64);
+
+        let index = |x: usize, y: usize| x + y * width;
+        let edges = [
+            (index(x, y), index(x1, y)),
+            (index(x, y1), index(x1, y1)),
+            (index(x, y), index(x, y1)),
+            (index(x1, y), index(x1, y1)),
+        ];
+
+        let at = |x: usize, y: usize| self.positions[index(x, y)];
+        let top = at(x, y) * (1.0 - tx) + at(x1, y) * tx;
+        let bottom = at(x, y1) * (1.0 - tx) + at(x1, y1) * tx;
+        (top * (1.0 - ty) + bottom * ty, edges)
+    }
+
+    /// Velocity of the cloth at a point in the world, in distance per step, blended bilinearly
+    /// from the corners of the grid cell that currently covers it. Returns zero anywhere not
+    /// covered by an intact cell, or everywhere for a cloth that lost its grid layout.
+    pub fn velocity_at(&self, pos: Vector2) -> Vector2 {
+        let Some((width, height)) = self.grid else {
+            return Vector2::ZERO;
+        };
+
+        let adjacency = self.adjacency();
+        let linked = |a: usize, b: usize| adjacency[a].contains(&b);
+
+        for y in 0..height.saturating_sub(1) {
+            for x in 0..width.saturating_sub(1) {
+                let top_left = y * width + x;
+                let corners = [
+                    top_left,
+                    top_left + 1,
+                    top_left + width + 1,
+                    top_left + width,
+                ];
+                let [a, b, c, d] = corners;
+                if !(linked(a, b) && linked(b, c) && linked(c, d) && linked(d, a)) {
+                    continue;
+                }
+
+                let [pa, pb, pc, pd] = corners.map(|i| self.positions[i]);
+                if let Some((u, v)) = inverse_bilinear(pos, pa, pb, pc, pd) {
+                    let [va, vb, vc, vd] = corners.map(|i| self.velocity(i));
+                    let top = va * (1.0 - u) + vb * u;
+                    let bottom = vd * (1.0 - u) + vc * u;
+                    return top * (1.0 - v) + bottom * v;
+                }
+            }
+        }
+        Vector2::ZERO
+    }
+
+    /// Whether intact sticks connect every pair of points, found in a single pass over the sticks
+    fn all_connected(&self, pairs: &[(usize, usize)]) -> bool {
+        let mut missing = pairs.to_vec();
+        for stick in self.sticks.iter().filter(|stick| !stick.broken) {
+            if missing.is_empty() {
+                break;
             }
+            missing.retain(|&(a, b)| {
+                !((stick.p1 == a && stick.p2 == b) || (stick.p1 == b && stick.p2 == a))
+            });
+        }
+        missing.is_empty()
+    }
 
-            let diff_factor = (self.length - dist) / dist;
-            diff * diff_factor * 0.5
+    /// Computes a unit normal for every point from its left and right neighbors in the grid,
+    /// pointing up for a cloth hanging flat. Points of a cloth that lost its grid layout get a zero normal.
+    pub fn compute_normals(&self) -> Vec<Vector2> {
+        let Some((width, _)) = self.grid else {
+            return vec![Vector2::ZERO; self.positions.len()];
         };
 
-        let mut p1 = self.p1.borrow_mut();
-        let mut p2 = self.p2.borrow_mut();
-        p1.position += offset;
-        p2.position -= offset;
+        (0..self.positions.len())
+            .map(|i| {
+                let x = i % width;
+                let left = if x > 0 { i - 1 } else { i };
+                let right = if x + 1 < width { i + 1 } else { i };
+                (self.positions[left] - self.positions[right])
+                    .perpendicular()
+                    .normalized()
+            })
+            .collect()
     }
-}
 
-impl Render for Stick {
-    fn render(&self, draw: &mut Draw) {
-        let p1 = self.p1.borrow();
-        let p2 = self.p2.borrow();
-        draw.line(
-            (p1.position.x as f32, p1.position.y as f32),
-            (p2.position.x as f32, p2.position.y as f32),
-        )
-        .color(if self.selected {
-            Color::RED
+    /// Estimates how sharply the cloth is folded at every point, as the angle in radians the cloth
+    /// turns through there along its row or column, whichever is larger. Flat regions are near 0
+    /// and a fold doubling the cloth back on itself approaches pi. Points on the edge or next to a
+    /// tear, and every point of a cloth that lost its grid layout, get 0.
+    pub fn curvature_map(&self) -> Vec<f64> {
+        let Some((width, height)) = self.grid else {
+            return vec![0.0; self.positions.len()];
+        };
+
+        let adjacency = self.adjacency();
+        let bend = |before: usize, i: usize, after: usize| {
+            if !adjacency[i].contains(&before) || !adjacency[i].contains(&after) {
+                return 0.0;
+            }
+            let incoming = self.positions[i] - self.positions[before];
+            let outgoing = self.positions[after] - self.positions[i];
+            incoming
+                .perpendicular()
+                .dot(&outgoing)
+                .atan2(incoming.dot(&outgoing))
+                .abs()
+        };
+
+        (0..self.positions.len())
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let row = if x > 0 && x + 1 < width {
+                    bend(i - 1, i, i + 1)
+                } else {
+                    0.0
+                };
+                let column = if y > 0 && y + 1 < height {
+                    bend(i - width, i, i + width)
+                } else {
+                    0.0
+                };
+                row.max(column)
+            })
+            .collect()
+    }
+
+    /// Root mean square of how far each intact stick is from its rest length
+    pub fn rms_length_error(&self) -> f64 {
+        let errors: Vec<f64> = self
+            .sticks
+            .iter()
+            .filter(|stick| !stick.broken)
+            .map(|stick| {
+                let dist = self.positions[stick.p1].distance(&self.positions[stick.p2]);
+                (dist - stick.length).powi(2)
+            })
+            .collect();
+
+        if errors.is_empty() {
+            return 0.0;
+        }
+        (errors.iter().sum::<f64>() / errors.len() as f64).sqrt()
+    }
+
+    /// Sum of the momentum of every free point, with velocities measured in distance per step.
+    /// Pinned points are left out since they are held in place externally.
+    pub fn total_momentum(&self) -> Vector2 {
+        (0..self.positions.len())
+            .filter(|&i| !self.pinned[i])
+            .fold(Vector2::ZERO, |total, i| {
+                total + self.velocity(i) * POINT_MASS
+            })
+    }
+
+    /// Moment of inertia of every point about the cloth's center of mass, the sum of each point's
+    /// mass times its squared distance from the center
+    pub fn moment_of_inertia(&self) -> f64 {
+        if self.positions.is_empty() {
+            return 0.0;
+        }
+
+        // Every point weighs the same, so the center of mass is the average position
+        let center = self
+            .positions
+            .iter()
+            .fold(Vector2::ZERO, |sum, position| sum + *position)
+            / self.positions.len() as f64;
+        self.positions
+            .iter()
+            .map(|position| POINT_MASS * (*position - center).magnitude_squared())
+            .sum()
+    }
+
+    /// Distance the point moved over the last step, as implied by verlet integration
+    fn velocity(&self, index: usize) -> Vector2 {
+        self.positions[index] - self.prev_positions[index]
+    }
+
+    /// Current and previous position of every point, which together fully describe its motion
+    pub fn verlet_state(&self) -> Vec<(Vector2, Vector2)> {
+        self.positions
+            .iter()
+            .copied()
+            .zip(self.prev_positions.iter().copied())
+            .collect()
+    }
+
+    /// Overwrites the current and previous position of every point.
+    ///
+    /// Panics if `state` doesn't have one entry per point.
+    pub fn set_verlet_state(&mut self, state: &[(Vector2, Vector2)]) {
+        assert_eq!(
+            state.len(),
+            self.positions.len(),
+            "expected one state per point"
+        );
+        for (i, &(position, prev_position)) in state.iter().enumerate() {
+            self.positions[i] = position;
+            self.prev_positions[i] = prev_position;
+        }
+    }
+
+    pub fn snapshot(&self) -> ClothSnapshot {
+        ClothSnapshot {
+            positions: self.positions.clone(),
+            broken: self.sticks.iter().map(|stick| stick.broken).collect(),
+        }
+    }
+
+    /// Writes every point to a CSV file at `path` with the columns `index,x,y,pinned`, one row
+    /// per point after a header row
+    pub fn export_csv(&self, path: &Path) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(file, "index,x,y,pinned")?;
+        for (i, position) in self.positions.iter().enumerate() {
+            writeln!(
+                file,
+                "{},{},{},{}",
+                i, position.x, position.y, self.pinned[i]
+            )?;
+        }
+        file.flush()
+    }
+
+    /// Lists the indices of the two points each stick connects
+    pub fn stick_pairs(&self) -> Vec<(usize, usize)> {
+        self.sticks
+            .iter()
+            .map(|stick| (stick.p1, stick.p2))
+            .collect()
+    }
+
+    /// Lists every intact stick as `(index, strain)`, most strained first
+    pub fn sticks_by_stress(&self) -> Vec<(usize, f64)> {
+        let mut sticks: Vec<(usize, f64)> = self
+            .sticks
+            .iter()
+            .enumerate()
+            .filter(|(_, stick)| !stick.broken)
+            .map(|(i, stick)| (i, stick.strain(&self.positions)))
+            .collect();
+        sticks.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        sticks
+    }
+
+    /// Indices of the intact sticks strained past `fraction` of the strain they break at, so a
+    /// fraction of 0.9 finds the sticks within 10% of tearing
+    pub fn sticks_near_breaking(&self, fraction: f64) -> Vec<usize> {
+        self.sticks
+            .iter()
+            .enumerate()
+            .filter(|(_, stick)| {
+                let break_strain = stick.elasticity + self.break_margin;
+                !stick.broken
+                    && break_strain.is_finite()
+                    && stick.strain(&self.positions) > fraction * break_strain
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Mean stretch ratio of the intact sticks, 1 for a cloth at rest. Values far above 1 are a sign
+    /// of the simulation becoming unstable.
+    pub fn average_stretch(&self) -> f64 {
+        let stretches: Vec<f64> = self
+            .sticks
+            .iter()
+            .filter(|stick| !stick.broken)
+            .map(|stick| stick.stretch(&self.positions))
+            .collect();
+
+        if stretches.is_empty() {
+            return 1.0;
+        }
+        stretches.iter().sum::<f64>() / stretches.len() as f64
+    }
+
+    /// Index of the intact stick with the highest stretch ratio
+    pub fn most_stressed_stick(&self) -> Option<usize> {
+        self.sticks
+            .iter()
+            .enumerate()
+            .filter(|(_, stick)| !stick.broken)
+            .map(|(i, stick)| (i, stick.stretch(&self.positions)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Breaks a stick, which is removed on the next update
+    pub fn tear_stick(&mut self, index: usize) {
+        self.sticks[index].broken = true;
+        self.any_broken = true;
+    }
+
+    /// Breaks every stick crossed by the path through `points`, the sticks are removed on the next update
+    pub fn cut_polyline(&mut self, points: &[Vector2]) {
+        for stick in &mut self.sticks {
+            let p1 = self.positions[stick.p1];
+            let p2 = self.positions[stick.p2];
+            if points
+                .windows(2)
+                .any(|segment| segments_intersect(p1, p2, segment[0], segment[1]))
+            {
+                stick.broken = true;
+                self.any_broken = true;
+            }
+        }
+    }
+
+    /// Reconnects the points of the most recently torn stick, returning false if there is nothing
+    /// left to undo
+    pub fn undo_tear(&mut self) -> bool {
+        let Some(mut stick) = self.tear_history.pop_back() else {
+            return false;
+        };
+        stick.broken = false;
+        stick.burning = None;
+        stick.overstretched = 0;
+        stick.selected = false;
+        self.add_stick(stick);
+        true
+    }
+
+    fn record_tear(&mut self, stick: Stick) {
+        if self.tear_history.len() == TEAR_HISTORY_LIMIT {
+            self.tear_history.pop_front();
+        }
+        self.tear_history.push_back(stick);
+    }
+
+    /// Current length of the cloth's boundary, including the edges of any torn holes
+    pub fn perimeter(&self) -> f64 {
+        self.boundary_sticks()
+            .into_iter()
+            .map(|i| {
+                let stick = &self.sticks[i];
+                self.positions[stick.p1].distance(&self.positions[stick.p2])
+            })
+            .sum()
+    }
+
+    /// Indices of the sticks bordering fewer than two intact quads, which are the sticks on the
+    /// outer edge of the grid or around a tear
+    fn boundary_sticks(&self) -> Vec<usize> {
+        let adjacency = self.adjacency();
+
+        // Counts the 4-cycles a -> b -> c -> d -> a going through the stick
+        let quads = |a: usize, b: usize| {
+            let mut count = 0;
+            for &c in adjacency[b].iter().filter(|&&c| c != a) {
+                for &d in adjacency[c].iter().filter(|&&d| d != a && d != b) {
+                    if adjacency[d].contains(&a) {
+                        count += 1;
+                    }
+                }
+            }
+            count
+        };
+
+        (0..self.sticks.len())
+            .filter(|&i| {
+                let stick = &self.sticks[i];
+                !stick.broken && quads(stick.p1, stick.p2) < 2
+            })
+            .collect()
+    }
+
+    /// Breaks the cloth up into one cloth per connected piece, keeping the positions, velocities
+    /// and pins of every point along with the settings of this cloth. Like clones, the fragments
+    /// start without a post iteration hook.
+    pub fn split_into_fragments(mut self) -> Vec<Cloth> {
+        let (labels, count) = self.component_labels();
+
+        let positions = mem::take(&mut self.positions);
+        let prev_positions = mem::take(&mut self.prev_positions);
+        let initial_positions = mem::take(&mut self.initial_positions);
+        let pin_targets = mem::take(&mut self.pin_targets);
+        let pinned = mem::take(&mut self.pinned);
+        let gravity_scales = mem::take(&mut self.gravity_scales);
+        let grabbable = mem::take(&mut self.grabbable);
+        let pin_strengths = mem::take(&mut self.pin_strengths);
+        let soft_pins = mem::take(&mut self.soft_pins);
+        let tethers = mem::take(&mut self.tethers);
+        let sticks = mem::take(&mut self.sticks);
+
+        // Leave only the settings behind to start every fragment from
+        self.grid = None;
+        self.point_slots = Slots::default();
+        self.stick_slots = Slots::default();
+        self.crack_tips.clear();
+        self.tear_history.clear();
+        // The hook was written for the unsplit cloth's points, and clones start without one anyway
+        self.post_iteration = PostIteration::default();
+        let mut fragments = vec![self; count];
+
+        // Index of every point within its fragment
+        let mut local = vec![0; positions.len()];
+        for (i, &label) in labels.iter().enumerate() {
+            let fragment = &mut fragments[label];
+            let index = fragment.add_point(positions[i]);
+            fragment.prev_positions[index] = prev_positions[i];
+            fragment.initial_positions[index] = initial_positions[i];
+            fragment.pin_targets[index] = pin_targets[i];
+            fragment.pinned[index] = pinned[i];
+            fragment.gravity_scales[index] = gravity_scales[i];
+            fragment.grabbable[index] = grabbable[i];
+            fragment.pin_strengths[index] = pin_strengths[i];
+            fragment.soft_pins[index] = soft_pins[i];
+            local[i] = index;
+        }
+
+        for mut tether in tethers {
+            let label = labels[tether.point];
+            tether.point = local[tether.point];
+            fragments[label].tethers.push(tether);
+        }
+
+        for mut stick in sticks.into_iter().filter(|stick| !stick.broken) {
+            let label = labels[stick.p1];
+            stick.p1 = local[stick.p1];
+            stick.p2 = local[stick.p2];
+            fragments[label].add_stick(stick);
+        }
+        fragments
+    }
+
+    /// Number of separate pieces the cloth has been torn into, counting loose points as pieces
+    pub fn connected_components(&self) -> usize {
+        self.component_labels().1
+    }
+
+    /// Labels every point with the index of the connected piece it belongs to, along with the
+    /// number of pieces
+    fn component_labels(&self) -> (Vec<usize>, usize) {
+        // Union-find over the intact sticks
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        let mut parent: Vec<usize> = (0..self.positions.len()).collect();
+        for stick in self.sticks.iter().filter(|stick| !stick.broken) {
+            let a = find(&mut parent, stick.p1);
+            let b = find(&mut parent, stick.p2);
+            parent[a] = b;
+        }
+
+        let mut labels = vec![usize::MAX; self.positions.len()];
+        let mut count = 0;
+        for i in 0..self.positions.len() {
+            let root = find(&mut parent, i);
+            if labels[root] == usize::MAX {
+                labels[root] = count;
+                count += 1;
+            }
+            labels[i] = labels[root];
+        }
+        (labels, count)
+    }
+
+    /// Lists the neighbors of every point by following the intact sticks
+    fn adjacency(&self) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); self.positions.len()];
+        for stick in self.sticks.iter().filter(|stick| !stick.broken) {
+            adjacency[stick.p1].push(stick.p2);
+            adjacency[stick.p2].push(stick.p1);
+        }
+        adjacency
+    }
+
+    /// Sets a wind that grows with height, `base` at the bottom edge of the resting cloth
+    /// plus `per_unit_height` for every unit above it
+    pub fn set_wind_shear(&mut self, base: Vector2, per_unit_height: Vector2) {
+        self.wind = base;
+        self.wind_shear = per_unit_height;
+    }
+
+    /// Adds a point that attracts the cloth with a force of `strength / distance^2`
+    pub fn add_gravity_well(&mut self, center: Vector2, strength: f64) {
+        self.gravity_wells.push((center, strength));
+    }
+
+    /// Adds a swirling force that pushes points within `radius` of `center` around it, clockwise on
+    /// screen for a positive `strength` and counterclockwise for a negative one. The force is
+    /// `strength` at the center and fades out to nothing at `radius`.
+    pub fn add_vortex(&mut self, center: Vector2, strength: f64, radius: f64) {
+        self.vortices.push((center, strength, radius));
+    }
+
+    /// Adds a solid surface running through the points. Its solid side is on the right of the
+    /// direction of travel on screen, so a polyline listed from left to right blocks cloth from above.
+    /// The obstacle is on the first layer.
+    pub fn add_polyline_obstacle(&mut self, points: Vec<Vector2>) {
+        self.add_polyline_obstacle_on_layer(points, 1);
+    }
+
+    /// Like `add_polyline_obstacle`, but on the layers set in the `layer` bitmask
+    pub fn add_polyline_obstacle_on_layer(&mut self, points: Vec<Vector2>, layer: u32) {
+        self.obstacles.push((Obstacle::Polyline(points), layer));
+    }
+
+    /// Sets the bitmask of obstacle layers the cloth collides with, obstacles sharing no layer with
+    /// the mask are passed through. Defaults to every layer.
+    pub fn set_collision_mask(&mut self, mask: u32) {
+        self.collision_mask = mask;
+    }
+
+    fn resolve_collisions(&mut self) {
+        let obstacles = self
+            .obstacles
+            .iter()
+            .filter(|(_, layer)| layer & self.collision_mask != 0);
+        for (obstacle, _) in obstacles {
+            for (i, position) in self.positions.iter_mut().enumerate() {
+                if !self.pinned[i] {
+                    obstacle.resolve(position);
+                }
+            }
+        }
+    }
+
+    /// Pushes the points inside the rectangle between two corners with `wind` during the next update
+    pub fn apply_wind_region(&mut self, rect: (Vector2, Vector2), wind: Vector2) {
+        self.region_winds.push((rect, wind));
+    }
+
+    /// Adds gusty wind that changes smoothly over space and time, pushing points with a force of up
+    /// to `amplitude` in each direction. `scale` is roughly the distance between gusts, smaller
+    /// values make the turbulence more chaotic. An amplitude of 0 turns it off.
+    ///
+    /// Panics if `scale` isn't positive while turning the turbulence on.
+    pub fn set_turbulence(&mut self, amplitude: f64, scale: f64) {
+        assert!(
+            amplitude == 0.0 || scale > 0.0,
+            "turbulence scale must be positive"
+        );
+        self.turbulence = (amplitude != 0.0).then_some((amplitude, scale));
+    }
+
+    /// Pushes the points within `radius` of the segment an object moved along from `from` to `to`
+    /// in the direction it moved during the next update. The push is strongest on the segment and
+    /// fades out to nothing at `radius`.
+    pub fn sweep(&mut self, from: Vector2, to: Vector2, radius: f64, push: f64) {
+        self.sweeps.push(((from, to), radius, push));
+    }
+
+    /// Sums the external forces acting on a point, given the bottom of the resting cloth and how
+    /// strongly the point feels gravity
+    fn force_at(&self, bottom: f64, position: Vector2, gravity_scale: f64) -> Vector2 {
+        // Screen space y points down, so height above the bottom is bottom - y
+        let rampup = if self.gravity_rampup > 0.0 {
+            (self.time / self.gravity_rampup).min(1.0)
+        } else {
+            1.0
+        };
+        let mut force = self.gravity * (gravity_scale * rampup)
+            + self.wind
+            + self.wind_shear * (bottom - position.y);
+
+        if let Some((amplitude, scale)) = self.turbulence {
+            let (x, y) = (position.x / scale, position.y / scale);
+            // Flip the seed for the vertical force so the two directions don't gust in lockstep
+            let noise = Vector2::new(
+                layered_noise(self.seed, x, y, self.time, TURBULENCE_OCTAVES),
+                layered_noise(!self.seed, x, y, self.time, TURBULENCE_OCTAVES),
+            );
+            force += noise * amplitude;
+        }
+
+        for (center, strength) in &self.gravity_wells {
+            let diff = *center - position;
+            let dist_sq = diff
+                .magnitude_squared()
+                .max(GRAVITY_WELL_MIN_DISTANCE * GRAVITY_WELL_MIN_DISTANCE);
+            force += diff.normalized() * (strength / dist_sq);
+        }
+
+        for (center, strength, radius) in &self.vortices {
+            let diff = position - *center;
+            let dist = diff.magnitude();
+            if dist < *radius {
+                force += diff.perpendicular().normalized() * (strength * (1.0 - dist / radius));
+            }
+        }
+
+        for ((a, b), wind) in &self.region_winds {
+            let inside_x = position.x >= a.x.min(b.x) && position.x <= a.x.max(b.x);
+            let inside_y = position.y >= a.y.min(b.y) && position.y <= a.y.max(b.y);
+            if inside_x && inside_y {
+                force += *wind;
+            }
+        }
+
+        for ((from, to), radius, push) in &self.sweeps {
+            let dist = point_segment_distance(position, *from, *to);
+            if dist < *radius {
+                force += (*to - *from).normalized() * (push * (1.0 - dist / radius));
+            }
+        }
+        force
+    }
+
+    /// Makes tears run through the cloth, each update continuing up to `max_per_update` recent
+    /// tears through their most strained neighboring stick, as long as it is strained by at least
+    /// half the strain it would break at, including the break margin
+    pub fn set_crack_propagation(&mut self, enabled: bool, max_per_update: usize) {
+        self.crack_propagation = enabled.then_some(max_per_update);
+        self.crack_tips.clear();
+    }
+
+    /// Breaks the most strained stick at each crack tip, up to `max` of them
+    fn propagate_cracks(&mut self, max: usize) {
+        let count = self.crack_tips.len().min(max);
+        let tips: Vec<usize> = self.crack_tips.drain(..count).collect();
+
+        for tip in tips {
+            let weakest = self
+                .sticks
+                .iter()
+                .enumerate()
+                .filter(|(_, stick)| !stick.broken && (stick.p1 == tip || stick.p2 == tip))
+                .map(|(i, stick)| (i, stick.strain(&self.positions)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            if let Some((i, strain)) = weakest {
+                let stick = &mut self.sticks[i];
+                if strain >= (stick.elasticity + self.break_margin) * CRACK_THRESHOLD {
+                    stick.broken = true;
+                    self.any_broken = true;
+                    self.crack_tips
+                        .push(if stick.p1 == tip { stick.p2 } else { stick.p1 });
+                }
+            }
+        }
+    }
+
+    /// Sets whether holding the right mouse button keeps cutting every update, rather than only
+    /// cutting once when it is pressed
+    pub fn set_continuous_cut(&mut self, continuous: bool) {
+        self.continuous_cut = continuous;
+    }
+
+    /// Sets whether every pinned point moves along with the mouse during updates, letting the whole
+    /// cloth be waved around by its anchors. Meant to be set every frame from whether a modifier
+    /// key is held.
+    pub fn set_anchors_follow_mouse(&mut self, follow: bool) {
+        self.anchors_follow_mouse = follow;
+    }
+
+    /// Sets a stick on fire. It ignites the sticks sharing a point with it after burning for a
+    /// while, and eventually breaks.
+    pub fn ignite(&mut self, index: usize) {
+        let stick = &mut self.sticks[index];
+        if stick.burning.is_none() {
+            stick.burning = Some(0.0);
+        }
+    }
+
+    /// Sets how many seconds a burning stick takes to ignite its neighbors and to break. The fire
+    /// only spreads if `spread_time` is shorter than `burn_out_time`.
+    pub fn set_burn_rate(&mut self, spread_time: f64, burn_out_time: f64) {
+        self.burn_spread_time = spread_time;
+        self.burn_out_time = burn_out_time;
+    }
+
+    fn advance_fire(&mut self, dt: f64) {
+        let mut spreading = vec![false; self.positions.len()];
+        let mut any_spreading = false;
+
+        for stick in &mut self.sticks {
+            let Some(time) = stick.burning else {
+                continue;
+            };
+            let new_time = time + dt;
+            if time < self.burn_spread_time && new_time >= self.burn_spread_time {
+                spreading[stick.p1] = true;
+                spreading[stick.p2] = true;
+                any_spreading = true;
+            }
+            if new_time >= self.burn_out_time {
+                stick.broken = true;
+                self.any_broken = true;
+            }
+            stick.burning = Some(new_time);
+        }
+
+        if any_spreading {
+            for stick in &mut self.sticks {
+                if stick.burning.is_none()
+                    && !stick.broken
+                    && (spreading[stick.p1] || spreading[stick.p2])
+                {
+                    stick.burning = Some(0.0);
+                }
+            }
+        }
+    }
+
+    /// Number of times the simulation has been stepped since it was created or reset
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Seconds of simulated time since the cloth was created or last reset, after the time scale
+    pub fn elapsed(&self) -> f64 {
+        self.time
+    }
+
+    /// Moves every point and pin back to its initial position at rest and restarts the step count
+    /// and clock. Torn sticks stay torn.
+    pub fn reset(&mut self) {
+        self.positions.clone_from(&self.initial_positions);
+        self.prev_positions.clone_from(&self.initial_positions);
+        self.pin_targets.clone_from(&self.initial_positions);
+        self.crack_tips.clear();
+        self.step_count = 0;
+        self.time = 0.0;
+    }
+
+    /// Makes the cloth reset itself at the end of any update that leaves a point at a non-finite
+    /// position or moving implausibly fast, so a simulation that blows up recovers on its own
+    pub fn set_auto_recover(&mut self, enabled: bool) {
+        self.auto_recover = enabled;
+    }
+
+    /// Whether every point's current and previous position is free of NaN and infinity
+    pub fn is_finite(&self) -> bool {
+        self.positions
+            .iter()
+            .chain(&self.prev_positions)
+            .all(|p| p.x.is_finite() && p.y.is_finite())
+    }
+
+    /// Whether any point has a non-finite position or moved further than is plausible in a step
+    fn unstable(&self) -> bool {
+        !self.is_finite()
+            || self
+                .positions
+                .iter()
+                .zip(&self.prev_positions)
+                .any(|(position, prev)| {
+                    (*position - *prev).magnitude_squared()
+                        > UNSTABLE_DISPLACEMENT * UNSTABLE_DISPLACEMENT
+                })
+    }
+
+    /// Stops simulating the cloth, turning updates into no-ops while it can still be drawn. This is
+    /// meant for cloths that have settled and should stay put as scenery, the cloth stays frozen
+    /// until `unfreeze` is called.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Resumes simulating a frozen cloth, picking up with the velocities it had when frozen
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Pins a free point where it currently is, or releases a pinned point
+    pub fn toggle_pin(&mut self, index: usize) {
+        if self.pinned[index] {
+            // Drop any velocity accumulated from before the point was pinned
+            self.prev_positions[index] = self.positions[index];
+        } else {
+            self.pin_targets[index] = self.positions[index];
+        }
+        self.pinned[index] = !self.pinned[index];
+    }
+
+    /// Sets how strongly a point is pulled by gravity, as a multiple of the cloth's gravity.
+    /// A scale of 0 makes the point weightless and a negative scale makes it float up.
+    pub fn set_gravity_scale(&mut self, index: usize, scale: f64) {
+        self.gravity_scales[index] = scale;
+    }
+
+    /// Limits the points that can be grabbed, cut or pinned with the mouse to the given ones, or
+    /// lets every point be selected again if `indices` is empty. Points added afterwards can be
+    /// selected.
+    pub fn set_grabbable(&mut self, indices: &[usize]) {
+        self.grabbable.fill(indices.is_empty());
+        for &i in indices {
+            self.grabbable[i] = true;
+        }
+    }
+
+    /// Nudges every free point in a random direction by up to `magnitude`, breaking the symmetry of
+    /// a freshly built cloth so it drapes more naturally. The points keep their velocity.
+    pub fn jitter(&mut self, magnitude: f64) {
+        for i in 0..self.positions.len() {
+            if self.pinned[i] {
+                continue;
+            }
+            let angle = self.rng.next_f64() * std::f64::consts::TAU;
+            // Taking the square root spreads the offsets evenly over the disc
+            let radius = self.rng.next_f64().sqrt() * magnitude;
+            let offset = Vector2::new(angle.cos(), angle.sin()) * radius;
+            self.positions[i] += offset;
+            self.prev_positions[i] += offset;
+        }
+    }
+
+    /// Index and position of the lowest point on screen, the one with the greatest y, or None if
+    /// the cloth has no points
+    pub fn lowest_point(&self) -> Option<(usize, Vector2)> {
+        self.positions
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.y.total_cmp(&b.y))
+    }
+
+    /// Positions every pinned point is held at, in point order
+    pub fn anchor_positions(&self) -> Vec<Vector2> {
+        self.pin_targets
+            .iter()
+            .zip(&self.pinned)
+            .filter(|(_, &pinned)| pinned)
+            .map(|(position, _)| *position)
+            .collect()
+    }
+
+    /// Holds a point near `anchor` with a spring instead of pinning it rigidly, pulling it with a
+    /// force of `stiffness` per unit of distance from the anchor. The point overshoots and bounces
+    /// around the anchor before settling, more so for stiffer springs and less drag.
+    ///
+    /// Soft pinning a point again replaces its spring, a stiffness of 0 removes it.
+    pub fn soft_pin(&mut self, index: usize, anchor: Vector2, stiffness: f64) {
+        self.soft_pins[index] = (stiffness != 0.0).then_some((anchor, stiffness));
+    }
+
+    /// Ties a point to a fixed `anchor` with a rope `length` long, which lets the point swing freely
+    /// within that distance but not beyond it. Like a stick, the tether snaps once stretched past
+    /// `elasticity` times its length.
+    pub fn tether(&mut self, index: usize, anchor: Vector2, length: f64, elasticity: f64) {
+        assert!(elasticity >= 0.0);
+        self.tethers.push(Tether {
+            point: index,
+            anchor,
+            length,
+            elasticity,
+        });
+    }
+
+    fn solve_tethers(&mut self) {
+        for tether in &self.tethers {
+            if !self.pinned[tether.point] {
+                tether.solve(&mut self.positions);
+            }
+        }
+    }
+
+    /// Indices of every point within `radius` of `center`
+    pub fn points_within(&self, center: Vector2, radius: f64) -> Vec<usize> {
+        self.positions
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| (**p - center).magnitude_squared() <= radius * radius)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Loosely pins the point at column `x` and row `y` of the grid, pulling it `stiffness` of the
+    /// way back to its initial position every update instead of holding it there exactly. Unlike
+    /// `soft_pin` this doesn't overshoot, it just lets the point sag a bit under load. A stiffness
+    /// of 0 releases the point.
+    ///
+    /// Panics if the cloth lost its grid layout.
+    pub fn soft_pin_in_grid(&mut self, x: usize, y: usize, stiffness: f64) {
+        let (width, height) = self.grid.expect("the cloth no longer has a grid layout");
+        assert!(x < width && y < height, "grid position out of bounds");
+        self.pin_strengths[y * width + x] = stiffness.clamp(0.0, 1.0);
+    }
+
+    /// Index of the closest grabbable point within `radius` of `position`
+    fn nearest_point(&self, position: Vector2, radius: f64) -> Option<usize> {
+        self.positions
+            .iter()
+            .map(|p| (*p - position).magnitude_squared())
+            .enumerate()
+            .filter(|&(i, dist_sq)| self.grabbable[i] && dist_sq <= radius * radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Sets the gravity and steps the simulation in one call, for gravity that changes every frame
+    pub fn update_with_gravity(
+        &mut self,
+        dt: f64,
+        mouse: &Mouse,
+        prev_mouse_position: Vector2,
+        gravity: Vector2,
+    ) {
+        self.set_gravity(gravity);
+        self.update(dt, mouse, prev_mouse_position);
+    }
+
+    /// How long each phase of the most recent update took
+    #[cfg(feature = "profiling")]
+    pub fn last_timings(&self) -> FrameTimings {
+        self.last_timings
+    }
+
+    pub fn update(&mut self, dt: f64, mouse: &Mouse, prev_mouse_position: Vector2) {
+        if self.frozen {
+            return;
+        }
+
+        #[cfg(feature = "profiling")]
+        let start = Instant::now();
+
+        let dt = dt * self.time_scale;
+        let mouse_position = Vector2::from(mouse.position());
+
+        // Move the pins themselves, the pinned points snap to them when integrated
+        if self.anchors_follow_mouse {
+            let delta = mouse_position - prev_mouse_position;
+            for (anchor, _) in self
+                .pin_targets
+                .iter_mut()
+                .zip(&self.pinned)
+                .filter(|(_, &pinned)| pinned)
+            {
+                *anchor += delta;
+            }
+        }
+
+        // Toggle the pin of the closest point once per middle click
+        let middle_down = mouse.middle_is_down();
+        if middle_down && !self.prev_middle_down {
+            if let Some(i) = self.nearest_point(mouse_position, CURSOR_RADIUS) {
+                self.toggle_pin(i);
+            }
+        }
+        self.prev_middle_down = middle_down;
+
+        let right_down = mouse.right_is_down();
+        let cutting = right_down && (self.continuous_cut || !self.prev_right_down);
+        self.prev_right_down = right_down;
+
+        let mut selected = vec![false; self.positions.len()];
+        let mut cut = false;
+        let mut drag_total = Vector2::ZERO;
+        let mut dragged = 0;
+        let bottom = self
+            .initial_positions
+            .iter()
+            .fold(f64::MIN, |bottom, position| bottom.max(position.y));
+
+        for (i, is_selected) in selected.iter_mut().enumerate() {
+            // Check if the point is within the mouse's selection radius
+            // Uses the square of the magnitude instead of distance since sqrt is expensive
+            let dist_sq = (self.positions[i] - mouse_position).magnitude_squared();
+            *is_selected = self.grabbable[i] && dist_sq <= CURSOR_RADIUS * CURSOR_RADIUS;
+
+            let mut force = self.force_at(bottom, self.positions[i], self.gravity_scales[i]);
+            if let Some((anchor, stiffness)) = self.soft_pins[i] {
+                force += (anchor - self.positions[i]) * stiffness;
+            }
+
+            // Apply force from mouse dragging
+            if *is_selected {
+                if mouse.left_is_down() {
+                    let drag_force = self.grab_force(i, dt, mouse_position, prev_mouse_position);
+                    drag_total += drag_force;
+                    dragged += 1;
+                    force += drag_force;
+                } else if cutting {
+                    cut = true;
+                }
+            }
+
+            self.update_point(i, dt, force);
+        }
+        self.last_drag_force = if dragged > 0 {
+            drag_total / dragged as f64
+        } else {
+            Vector2::ZERO
+        };
+
+        #[cfg(feature = "profiling")]
+        let integrated = Instant::now();
+
+        // Highlight sticks touching a selected point and cut the ones they hold if requested
+        let grid = self.grid;
+        for stick in &mut self.sticks {
+            stick.selected = selected[stick.p1] || selected[stick.p2];
+            if cut && stick.selected && cuts(grid, &selected, stick) {
+                stick.broken = true;
+                self.any_broken = true;
+            }
+        }
+
+        self.advance_fire(dt);
+
+        if let Some(max) = self.crack_propagation {
+            self.propagate_cracks(max);
+        }
+
+        // Apply stick constraints and remove broken sticks
+        let correction = self.over_relaxation * self.iteration_stiffness();
+        let removing = self.any_broken;
+        self.any_broken = false;
+        let mut to_remove = Vec::new();
+        for (i, stick) in self.sticks.iter_mut().enumerate() {
+            let was_broken = stick.broken;
+            if removing && was_broken {
+                to_remove.push(i);
+            }
+
+            stick.update(
+                &mut self.positions,
+                correction,
+                self.break_margin,
+                self.break_delay,
+            );
+            if !stick.broken && stick.damping > 0.0 {
+                stick.damp(&mut self.positions, &self.prev_positions);
+            }
+
+            // Sticks that snapped this update are removed on the next one and become the start of a crack
+            if stick.broken && !was_broken {
+                self.any_broken = true;
+                if self.crack_propagation.is_some() {
+                    self.crack_tips.extend([stick.p1, stick.p2]);
+                }
+            }
+        }
+        let positions = &self.positions;
+        self.tethers
+            .retain(|tether| !tether.overstretched(positions));
+        self.solve_tethers();
+        self.resolve_collisions();
+        self.snap_pinned_points();
+        self.run_post_iteration();
+
+        // Stopping early would leave a cloth with less than full stiffness softer than it was set to,
+        // since the stiffness is spread over every iteration
+        let early_exit = self.stiffness == 1.0;
+        let mut iterations = 1;
+        while iterations < self.max_iterations && !(early_exit && self.converged()) {
+            for stick in self.sticks.iter().filter(|stick| !stick.broken) {
+                stick.solve(&mut self.positions, correction);
+            }
+            self.solve_tethers();
+            self.resolve_collisions();
+            self.snap_pinned_points();
+            self.run_post_iteration();
+            iterations += 1;
+        }
+        self.last_iteration_count = iterations;
+
+        if removing {
+            for stick in self.remove_sticks(to_remove) {
+                self.record_tear(stick);
+            }
+        }
+
+        if self.smoothing > 0.0 {
+            self.smooth();
+        }
+
+        self.region_winds.clear();
+        self.sweeps.clear();
+        self.step_count += 1;
+        self.time += dt;
+
+        if self.auto_recover && self.unstable() {
+            self.reset();
+        }
+
+        #[cfg(feature = "profiling")]
+        {
+            let end = Instant::now();
+            self.last_timings = FrameTimings {
+                integration: integrated - start,
+                constraints: end - integrated,
+                total: end - start,
+            };
+        }
+    }
+
+    /// Force on a grabbed point from dragging the mouse
+    fn grab_force(
+        &self,
+        index: usize,
+        dt: f64,
+        mouse_position: Vector2,
+        prev_mouse_position: Vector2,
+    ) -> Vector2 {
+        match self.grab_mode {
+            GrabMode::Force => {
+                let diff = mouse_position - prev_mouse_position;
+                let limit = if self.elasticity.is_finite() {
+                    self.elasticity
+                } else {
+                    UNBREAKABLE_DRAG_LIMIT
+                };
+                let clamped =
+                    Vector2::new(diff.x.clamp(-limit, limit), diff.y.clamp(-limit, limit));
+                clamped * self.drag_strength
+            }
+            GrabMode::Spring { stiffness, damping } => {
+                let velocity = if dt > 0.0 {
+                    self.velocity(index) / dt
+                } else {
+                    Vector2::ZERO
+                };
+                (mouse_position - self.positions[index]) * stiffness - velocity * damping
+            }
+        }
+    }
+
+    fn update_point(&mut self, index: usize, dt: f64, acceleration: Vector2) {
+        if self.pinned[index] {
+            self.positions[index] = self.pin_targets[index];
+            return;
+        }
+
+        // Solve for new position using verlet integration
+        let position = self.positions[index];
+        let new_position = position
+            + (position - self.prev_positions[index]) * (1.0 - self.drag)
+            + acceleration * (1.0 - self.drag) * dt * dt;
+        let pull = (self.initial_positions[index] - new_position) * self.pin_strengths[index];
+        self.prev_positions[index] = position;
+        self.positions[index] = new_position + pull;
+    }
+
+    /// Removes the sticks at the given ascending indices, returning them in the same order
+    fn remove_sticks(&mut self, indices: Vec<usize>) -> Vec<Stick> {
+        let mut removed = Vec::with_capacity(indices.len());
+        for i in indices.iter().rev() {
+            removed.push(self.sticks.remove(*i));
+            self.stick_slots.remove(*i);
+        }
+        removed.reverse();
+        removed
+    }
+
+    /// Draws the cloth in the current render mode
+    pub fn draw(&self, draw: &mut Draw) {
+        match self.render_mode {
+            RenderMode::Wireframe => self.draw_wireframe(draw),
+            RenderMode::Filled => self.draw_filled(draw),
+            RenderMode::Stress => self.draw_stress(draw),
+            RenderMode::Outline => self.draw_outline(draw, Color::WHITE),
+            RenderMode::FilledOutline => self.draw_filled_outline(draw),
+        }
+
+        for tether in &self.tethers {
+            let point = self.positions[tether.point];
+            draw.line(
+                (tether.anchor.x as f32, tether.anchor.y as f32),
+                (point.x as f32, point.y as f32),
+            )
+            .color(Color::GRAY);
+        }
+    }
+
+    /// Sets what `draw` shows, see `RenderMode` for the options
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        self.render_mode = mode;
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    /// Draws every stick as a line
+    pub fn draw_wireframe(&self, draw: &mut Draw) {
+        for stick in &self.sticks {
+            stick.render(&self.positions, draw, self.line_style);
+        }
+    }
+
+    /// Fills every cell of the grid whose four sides are intact with two triangles. A cloth that
+    /// lost its grid layout is drawn as a wireframe instead.
+    pub fn draw_filled(&self, draw: &mut Draw) {
+        if !self.fill_cells(draw, Color::WHITE) {
+            self.draw_wireframe(draw);
+        }
+    }
+
+    /// Fills the intact cells of the grid in gray and draws the boundary of the cloth on top,
+    /// showing the shape of the cloth without its interior sticks. A cloth that lost its grid
+    /// layout only has its boundary drawn.
+    pub fn draw_filled_outline(&self, draw: &mut Draw) {
+        self.fill_cells(draw, Color::GRAY);
+        self.draw_outline(draw, Color::WHITE);
+    }
+
+    /// Fills every cell of the grid whose four sides are intact, returning false without drawing
+    /// anything if the cloth lost its grid layout
+    fn fill_cells(&self, draw: &mut Draw, color: Color) -> bool {
+        let Some((width, height)) = self.grid else {
+            return false;
+        };
+
+        let adjacency = self.adjacency();
+        let linked = |a: usize, b: usize| adjacency[a].contains(&b);
+        for y in 0..height.saturating_sub(1) {
+            for x in 0..width.saturating_sub(1) {
+                let top_left = y * width + x;
+                let [a, b, c, d] = [
+                    top_left,
+                    top_left + 1,
+                    top_left + width + 1,
+                    top_left + width,
+                ];
+                if linked(a, b) && linked(b, c) && linked(c, d) && linked(d, a) {
+                    let [a, b, c, d] = [a, b, c, d].map(|i| {
+                        let position = self.positions[i];
+                        (position.x as f32, position.y as f32)
+                    });
+                    draw.triangle(a, b, c).color(color);
+                    draw.triangle(a, c, d).color(color);
+                }
+            }
+        }
+        true
+    }
+
+    /// Draws every intact stick in the color returned by `color_fn`, which is given the index of
+    /// the stick and the positions of its two points
+    pub fn draw_colored(
+        &self,
+        draw: &mut Draw,
+        color_fn: impl Fn(usize, Vector2, Vector2) -> Color,
+    ) {
+        for (i, stick) in self.sticks.iter().enumerate() {
+            if stick.broken {
+                continue;
+            }
+            let color = color_fn(i, self.positions[stick.p1], self.positions[stick.p2]);
+            stick.render_colored(&self.positions, draw, color, self.line_style);
+        }
+    }
+
+    /// Draws every stick colored by how strained it is
+    pub fn draw_stress(&self, draw: &mut Draw) {
+        for stick in &self.sticks {
+            let color = stress_color(stick.strain(&self.positions), self.stress_color_max);
+            stick.render_colored(&self.positions, draw, color, self.line_style);
+        }
+    }
+
+    /// Sets whether sticks are drawn as solid or dashed lines
+    pub fn set_line_style(&mut self, style: LineStyle) {
+        self.line_style = style;
+    }
+
+    /// Sets the strain at which `draw_stress` reaches its most intense color, more strained sticks
+    /// are drawn the same. Defaults to the elasticity, where sticks break, or to 0.5 for an
+    /// unbreakable cloth.
+    pub fn set_stress_color_max(&mut self, max: f64) {
+        self.stress_color_max = max;
+    }
+
+    /// Draws every stick faded by the depth of its midpoint, where a depth of 1 is fully opaque and
+    /// 0 fully transparent
+    pub fn draw_with_depth(&self, draw: &mut Draw, depth_fn: impl Fn(Vector2) -> f32) {
+        for stick in &self.sticks {
+            let midpoint = (self.positions[stick.p1] + self.positions[stick.p2]) / 2.0;
+            let alpha = depth_alpha(depth_fn(midpoint));
+            stick.render_colored(
+                &self.positions,
+                draw,
+                Color::new(1.0, 1.0, 1.0, alpha),
+                self.line_style,
+            );
+        }
+    }
+
+    /// Draws only the boundary of the cloth, including the edges of any torn holes
+    pub fn draw_outline(&self, draw: &mut Draw, color: Color) {
+        for i in self.boundary_sticks() {
+            self.sticks[i].render_colored(&self.positions, draw, color, self.line_style);
+        }
+    }
+
+    /// Draws every point as a circle whose radius grows by `scale` per unit of distance
+    /// travelled since the last step, clamped to `max_radius`
+    pub fn draw_velocity_points(&self, draw: &mut Draw, scale: f64, max_radius: f64) {
+        for (position, prev_position) in self.positions.iter().zip(&self.prev_positions) {
+            let speed = (*position - *prev_position).magnitude();
+            draw.circle(velocity_radius(speed, scale, max_radius) as f32)
+                .position(position.x as f32, position.y as f32);
+        }
+    }
+
+    /// Top left and bottom right corners of the smallest box around every point, or None if the
+    /// cloth has no points
+    pub fn bounds(&self) -> Option<(Vector2, Vector2)> {
+        bounding_box(&self.positions)
+    }
+
+    /// Camera that centers the cloth in a viewport of the given size and zooms it to fill as much
+    /// of the viewport as it can without cropping, leaving empty bars along the other sides.
+    /// Meant to be recomputed whenever the window is resized.
+    pub fn fit_transform(&self, viewport: (f32, f32)) -> Camera {
+        let Some((min, max)) = self.bounds() else {
+            return Camera::default();
+        };
+
+        let size = max - min;
+        let (viewport_width, viewport_height) = (viewport.0 as f64, viewport.1 as f64);
+        // A cloth that is flat along one axis is fit along the other
+        let zoom = match (size.x > 0.0, size.y > 0.0) {
+            (true, true) => (viewport_width / size.x).min(viewport_height / size.y),
+            (true, false) => viewport_width / size.x,
+            (false, true) => viewport_height / size.y,
+            (false, false) => 1.0,
+        };
+
+        let center = (min + max) / 2.0;
+        let half_viewport = Vector2::new(viewport_width, viewport_height) / (2.0 * zoom);
+        Camera::new(center - half_viewport, zoom)
+    }
+
+    /// Draws the cloth as seen through the camera
+    pub fn draw_with_camera(&self, draw: &mut Draw, camera: &Camera) {
+        let positions: Vec<Vector2> = self
+            .positions
+            .iter()
+            .map(|position| camera.world_to_screen(*position))
+            .collect();
+        for stick in &self.sticks {
+            stick.render(&positions, draw, self.line_style);
+        }
+    }
+
+    /// Draws every stick as a filled quad `width` wide instead of a line, which holds up better
+    /// than thin lines when zoomed. Ignores the line style.
+    pub fn draw_hq(&self, draw: &mut Draw, width: f64) {
+        for stick in &self.sticks {
+            stick.render_quad(&self.positions, draw, width);
+        }
+    }
+
+    /// Draws `cols` by `rows` copies of the cloth side by side, each offset from the last by the size
+    /// of the cloth at rest plus `gap`
+    pub fn draw_tiled(&self, draw: &mut Draw, cols: usize, rows: usize, gap: Vector2) {
+        for (stick, (a, b)) in self.tiled_segments(cols, rows, gap) {
+            draw.line((a.x as f32, a.y as f32), (b.x as f32, b.y as f32))
+                .color(stick.color());
+        }
+    }
+
+    /// Every piece of line drawn by `draw_tiled`, along with the stick it belongs to
+    fn tiled_segments(
+        &self,
+        cols: usize,
+        rows: usize,
+        gap: Vector2,
+    ) -> Vec<(&Stick, (Vector2, Vector2))> {
+        let Some((min, max)) = bounding_box(&self.initial_positions) else {
+            return Vec::new();
+        };
+        let step = max - min + gap;
+
+        let mut segments = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let offset = Vector2::new(step.x * col as f64, step.y * row as f64);
+                let positions: Vec<Vector2> = self
+                    .positions
+                    .iter()
+                    .map(|position| *position + offset)
+                    .collect();
+                for stick in &self.sticks {
+                    segments.extend(
+                        stick
+                            .segments(&positions, self.line_style)
+                            .into_iter()
+                            .map(|segment| (stick, segment)),
+                    );
+                }
+            }
+        }
+        segments
+    }
+
+    /// Draws only the sticks whose stretch ratio (current length / rest length) is within the range
+    pub fn draw_filtered(&self, draw: &mut Draw, min_stretch: f64, max_stretch: f64) {
+        for i in self.sticks_in_stretch_range(min_stretch, max_stretch) {
+            self.sticks[i].render(&self.positions, draw, self.line_style);
+        }
+    }
+
+    /// Indices of the sticks drawn by `draw_filtered`
+    fn sticks_in_stretch_range(&self, min_stretch: f64, max_stretch: f64) -> Vec<usize> {
+        self.sticks
+            .iter()
+            .enumerate()
+            .filter(|(_, stick)| {
+                let stretch = stick.stretch(&self.positions);
+                stretch >= min_stretch && stretch <= max_stretch
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Whether cutting at the selected points breaks `stick`. On a grid each point holds the stick to
+/// its right and the one below it, or to its left and above it on the last column and row, and
+/// only cuts those. Without a grid every stick touching a selected point is cut.
+fn cuts(grid: Option<(usize, usize)>, selected: &[bool], stick: &Stick) -> bool {
+    let Some((width, height)) = grid else {
+        return selected[stick.p1] || selected[stick.p2];
+    };
+
+    let holds = |point: usize, other: usize| {
+        let (x, y) = (point % width, point / width);
+        let horizontal = if x + 1 < width {
+            Some(point + 1)
+        } else {
+            x.checked_sub(1).map(|_| point - 1)
+        };
+        let vertical = if y + 1 < height {
+            Some(point + width)
+        } else {
+            y.checked_sub(1).map(|_| point - width)
+        };
+        selected[point] && (horizontal == Some(other) || vertical == Some(other))
+    };
+    holds(stick.p1, stick.p2) || holds(stick.p2, stick.p1)
+}
+
+/// Smallest and largest coordinates of the points, or None if there are none
+fn bounding_box(points: &[Vector2]) -> Option<(Vector2, Vector2)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), p| {
+        (
+            Vector2::new(min.x.min(p.x), min.y.min(p.y)),
+            Vector2::new(max.x.max(p.x), max.y.max(p.y)),
+        )
+    }))
+}
+
+/// Corners of a rectangle `width` wide centered on the line from `p1` to `p2`, going around it
+fn stick_quad(p1: Vector2, p2: Vector2, width: f64) -> [Vector2; 4] {
+    let offset = (p2 - p1).perpendicular().normalized() * (width / 2.0);
+    [p1 + offset, p2 + offset, p2 - offset, p1 - offset]
+}
+
+/// Splits the line from `p1` to `p2` into dashes `on` long separated by gaps `off` long, starting
+/// with a dash at `p1`. The last dash is cut short at `p2`.
+fn dash_segments(p1: Vector2, p2: Vector2, on: f64, off: f64) -> Vec<(Vector2, Vector2)> {
+    let length = p1.distance(&p2);
+    if length == 0.0 || on <= 0.0 {
+        return Vec::new();
+    }
+
+    let direction = (p2 - p1) / length;
+    let mut segments = Vec::new();
+    let mut start = 0.0;
+    while start < length {
+        let end = (start + on).min(length);
+        segments.push((p1 + direction * start, p1 + direction * end));
+        start += on + off.max(0.0);
+    }
+    segments
+}
+
+/// Color of a stick drawn by `Cloth::draw_stress`, fading from white when relaxed to red at `max` strain
+fn stress_color(strain: f64, max: f64) -> Color {
+    let t = if max > 0.0 {
+        (strain / max).clamp(0.0, 1.0) as f32
+    } else {
+        1.0
+    };
+    Color::new(1.0, 1.0 - t, 1.0 - t, 1.0)
+}
+
+/// Alpha of a stick drawn by `Cloth::draw_with_depth` at the given depth
+fn depth_alpha(depth: f32) -> f32 {
+    depth.clamp(0.0, 1.0)
+}
+
+/// Radius of a point drawn by `Cloth::draw_velocity_points`, growing with speed up to `max_radius`
+fn velocity_radius(speed: f64, scale: f64, max_radius: f64) -> f64 {
+    (POINT_RADIUS + speed * scale).min(max_radius)
+}
+
+impl Render for Cloth {
+    fn render(&self, draw: &mut Draw) {
+        self.draw(draw);
+    }
+}
+
+#[derive(Clone)]
+struct Stick {
+    // Indices into the cloth's point arrays, in the order the points were passed at construction
+    p1: usize,
+    p2: usize,
+    length: f64,
+    elasticity: f64,
+    stiffness: f64,
+    // Seconds the stick has been on fire for
+    burning: Option<f64>,
+    // Whether the stick goes slack when compressed
+    one_way: bool,
+    // Consecutive updates the stick has been stretched past its elasticity for
+    overstretched: u32,
+    // Fraction of the points' relative speed along the stick removed every update
+    damping: f64,
+    selected: bool,
+    broken: bool,
+}
+
+impl Stick {
+    fn new(p1: usize, p2: usize, length: f64, elasticity: f64) -> Self {
+        // Elasticity should be greater than 0 since its the percent of the length
+        // that the stick can stretch before breaking.
+        assert!(elasticity >= 0.0);
+        // A stick connecting a point to itself has no direction to correct along
+        // and would divide by its zero length when updated.
+        assert_ne!(p1, p2, "stick cannot connect a point to itself");
+        Stick {
+            p1,
+            p2,
+            length,
+            elasticity,
+            stiffness: 1.0,
+            burning: None,
+            one_way: false,
+            overstretched: 0,
+            damping: 0.0,
+            selected: false,
+            broken: false,
+        }
+    }
+
+    fn stretch(&self, positions: &[Vector2]) -> f64 {
+        positions[self.p1].distance(&positions[self.p2]) / self.length
+    }
+
+    /// How far the stick is stretched past its rest length, relative to the rest length
+    fn strain(&self, positions: &[Vector2]) -> f64 {
+        self.stretch(positions) - 1.0
+    }
+
+    fn update(
+        &mut self,
+        positions: &mut [Vector2],
+        over_relaxation: f64,
+        break_margin: f64,
+        break_delay: u32,
+    ) {
+        // Break the stick if it stretches too much for too long, unless it has infinite elasticity
+        let dist = positions[self.p1].distance(&positions[self.p2]);
+        if self.elasticity.is_finite()
+            && dist > self.length * (1.0 + self.elasticity + break_margin)
+        {
+            self.overstretched += 1;
+            if self.overstretched > break_delay {
+                self.broken = true;
+            }
         } else {
+            self.overstretched = 0;
+        }
+
+        self.solve(positions, over_relaxation);
+    }
+
+    /// Slows down the points moving towards or away from each other along the stick, leaving
+    /// their motion across it alone
+    fn damp(&self, positions: &mut [Vector2], prev_positions: &[Vector2]) {
+        let axis = (positions[self.p1] - positions[self.p2]).normalized();
+        let relative_velocity = (positions[self.p1] - prev_positions[self.p1])
+            - (positions[self.p2] - prev_positions[self.p2]);
+        let offset = axis * (relative_velocity.dot(&axis) * self.damping * 0.5);
+        positions[self.p1] -= offset;
+        positions[self.p2] += offset;
+    }
+
+    /// Moves both points towards the rest length without checking whether the stick breaks
+    fn solve(&self, positions: &mut [Vector2], over_relaxation: f64) {
+        let diff = positions[self.p1] - positions[self.p2];
+        let dist = diff.magnitude();
+
+        if self.one_way && dist < self.length {
+            return;
+        }
+
+        let diff_factor = (self.length - dist) / dist;
+        let offset = diff * diff_factor * 0.5 * self.stiffness * over_relaxation;
+
+        positions[self.p1] += offset;
+        positions[self.p2] -= offset;
+    }
+
+    fn color(&self) -> Color {
+        if self.selected {
+            Color::RED
+        } else if self.burning.is_some() {
+            Color::ORANGE
+        } else {
+            Color::WHITE
+        }
+    }
+
+    fn render(&self, positions: &[Vector2], draw: &mut Draw, style: LineStyle) {
+        self.render_colored(positions, draw, self.color(), style);
+    }
+
+    /// Draws the stick as a quad `width` wide out of two triangles
+    fn render_quad(&self, positions: &[Vector2], draw: &mut Draw, width: f64) {
+        let [a, b, c, d] = stick_quad(positions[self.p1], positions[self.p2], width)
+            .map(|corner| (corner.x as f32, corner.y as f32));
+        let color = self.color();
+        draw.triangle(a, b, c).color(color);
+        draw.triangle(a, c, d).color(color);
+    }
+
+    fn render_colored(
+        &self,
+        positions: &[Vector2],
+        draw: &mut Draw,
+        color: Color,
+        style: LineStyle,
+    ) {
+        for (a, b) in self.segments(positions, style) {
+            draw.line((a.x as f32, a.y as f32), (b.x as f32, b.y as f32))
+                .color(color);
+        }
+    }
+
+    /// Pieces of line the stick is drawn as in the given style
+    fn segments(&self, positions: &[Vector2], style: LineStyle) -> Vec<(Vector2, Vector2)> {
+        let p1 = positions[self.p1];
+        let p2 = positions[self.p2];
+        match style {
+            LineStyle::Solid => vec![(p1, p2)],
+            LineStyle::Dashed { on, off } => dash_segments(p1, p2, on as f64, off as f64),
+        }
+    }
+}
+
+/// Rope from a cloth point to a fixed anchor that only pulls once taut
+#[derive(Clone)]
+struct Tether {
+    point: usize,
+    anchor: Vector2,
+    length: f64,
+    elasticity: f64,
+}
+
+impl Tether {
+    fn overstretched(&self, positions: &[Vector2]) -> bool {
+        self.elasticity.is_finite()
+            && positions[self.point].distance(&self.anchor) > self.length * (1.0 + self.elasticity)
+    }
+
+    /// Pulls the point back to the length of the tether if it is too far from the anchor. The
+    /// anchor can't move so the point takes the whole correction.
+    fn solve(&self, positions: &mut [Vector2]) {
+        let diff = positions[self.point] - self.anchor;
+        if diff.magnitude() > self.length {
+            positions[self.point] = self.anchor + diff.normalized() * self.length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notan::prelude::MouseButton;
+
+    const DT: f64 = 1.0 / 60.0;
+
+    fn close(a: Vector2, b: Vector2) -> bool {
+        a.distance(&b) < 1e-9
+    }
+
+    /// Steps the cloth without the mouse doing anything
+    fn run(cloth: &mut Cloth, steps: usize) {
+        let mouse = Mouse::default();
+        for _ in 0..steps {
+            cloth.update(DT, &mouse, Vector2::ZERO);
+        }
+    }
+
+    /// Mouse at a position with the given buttons held down
+    fn mouse_at(x: f32, y: f32, buttons: &[MouseButton]) -> Mouse {
+        let mut mouse = Mouse {
+            x,
+            y,
+            ..Default::default()
+        };
+        for &button in buttons {
+            mouse.down.insert(button, 0.0);
+        }
+        mouse
+    }
+
+    #[test]
+    fn merge_appends_points_and_sticks() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        let other = Cloth::new(4, 2, 10, 100, 0, 1.0);
+        let points = cloth.len_points() + other.len_points();
+        let sticks = cloth.len_sticks() + other.len_sticks();
+        let other_pairs = other.stick_pairs();
+
+        cloth.merge(other);
+        assert_eq!(cloth.len_points(), points);
+        assert_eq!(cloth.len_sticks(), sticks);
+        assert_eq!(cloth.anchor_positions().len(), 2 + 2);
+
+        // The appended sticks point at the appended points
+        let merged_pairs = &cloth.stick_pairs()[12..];
+        for (&(a, b), &(c, d)) in other_pairs.iter().zip(merged_pairs) {
+            assert_eq!((a + 9, b + 9), (c, d));
+        }
+    }
+
+    #[test]
+    fn remove_point_drops_its_sticks() {
+        // Point 4 is the middle of a 3x3 grid and has four sticks
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        let sticks = cloth.len_sticks();
+        let handle = cloth.point_handle(4).unwrap();
+
+        let removed = cloth.remove_point(handle).unwrap();
+        assert!(close(removed, Vector2::new(10.0, 10.0)));
+        assert_eq!(cloth.len_points(), 8);
+        assert_eq!(cloth.len_sticks(), sticks - 4);
+
+        // The outer ring is untouched, with the points after the removed one shifted down
+        let mut pairs = cloth.stick_pairs();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            [
+                (1, 0),
+                (2, 1),
+                (3, 0),
+                (4, 2),
+                (5, 3),
+                (6, 5),
+                (7, 4),
+                (7, 6)
+            ]
+        );
+        assert!(close(cloth.positions[4], Vector2::new(20.0, 10.0)));
+    }
+
+    #[test]
+    fn filtered_drawing_keeps_sticks_in_range() {
+        // Pulling the last point of a 3x1 strip out stretches only the last stick, to 1.5
+        let mut cloth = Cloth::unpinned(3, 1, 10, 0, 0, f64::INFINITY);
+        cloth.positions[2].x = 25.0;
+
+        assert_eq!(cloth.sticks_in_stretch_range(1.4, 1.6), [1]);
+        assert_eq!(cloth.sticks_in_stretch_range(0.9, 1.1), [0]);
+        assert_eq!(cloth.sticks_in_stretch_range(0.0, 2.0), [0, 1]);
+        assert!(cloth.sticks_in_stretch_range(1.1, 1.4).is_empty());
+        cloth.draw_filtered(&mut Draw::new(100, 100), 1.4, 1.6);
+    }
+
+    #[test]
+    fn stale_point_handle_does_not_resolve() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        let removed = cloth.point_handle(4).unwrap();
+        let last = cloth.point_handle(8).unwrap();
+        cloth.remove_point(removed);
+        assert_eq!(cloth.point_index(removed), None);
+        assert_eq!(cloth.point_index(last), Some(7));
+
+        // The new point takes over the freed slot under a new generation
+        let inserted = cloth.insert_point(Vector2::new(50.0, 50.0), false);
+        assert_ne!(inserted, removed);
+        assert_eq!(cloth.point_index(inserted), Some(8));
+        assert_eq!(cloth.point_index(removed), None);
+        assert!(cloth.position(removed).is_none());
+        assert!(cloth.remove_point(removed).is_none());
+    }
+
+    #[test]
+    fn wind_shear_grows_with_height() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        cloth.set_wind_shear(Vector2::new(5.0, 0.0), Vector2::new(2.0, 0.0));
+
+        // The bottom row of the resting cloth is at y = 20
+        let top = cloth.force_at(20.0, Vector2::new(0.0, 0.0), 1.0);
+        let bottom = cloth.force_at(20.0, Vector2::new(0.0, 20.0), 1.0);
+        assert!(top.x > bottom.x);
+        assert!((bottom.x - 5.0).abs() < 1e-9);
+        assert!((top.x - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn velocity_radius_grows_with_speed() {
+        assert_eq!(velocity_radius(0.0, 2.0, 5.0), POINT_RADIUS);
+        assert_eq!(velocity_radius(1.5, 2.0, 5.0), POINT_RADIUS + 3.0);
+        assert_eq!(velocity_radius(100.0, 2.0, 5.0), 5.0);
+    }
+
+    #[test]
+    fn shear_skews_every_position() {
+        let mut cloth = Cloth::new(2, 2, 10, 0, 0, 1.0);
+        cloth.shear(0.5, 0.1);
+
+        let expected = [(0.0, 0.0), (10.0, 1.0), (5.0, 10.0), (15.0, 11.0)];
+        for (i, &(x, y)) in expected.iter().enumerate() {
+            let point = Vector2::new(x, y);
+            assert!(close(cloth.positions[i], point));
+            assert!(close(cloth.prev_positions[i], point));
+            assert!(close(cloth.initial_positions[i], point));
+        }
+        assert!(cloth.sticks.iter().all(|stick| stick.length == 10.0));
+    }
+
+    #[test]
+    fn anchor_reinforcement_reduces_strain_at_anchors() {
+        let mut uniform = Cloth::new(10, 10, 10, 0, 0, f64::INFINITY);
+        let mut reinforced = uniform.clone();
+        reinforced.set_anchor_reinforcement(2, 1.8);
+        run(&mut uniform, 60);
+        run(&mut reinforced, 60);
+
+        let near_anchors: Vec<usize> = (0..reinforced.sticks.len())
+            .filter(|&i| reinforced.sticks[i].stiffness != 1.0)
+            .collect();
+        assert!(!near_anchors.is_empty());
+        let mean_strain = |cloth: &Cloth| {
+            near_anchors
+                .iter()
+                .map(|&i| cloth.sticks[i].strain(&cloth.positions))
+                .sum::<f64>()
+                / near_anchors.len() as f64
+        };
+        assert!(mean_strain(&reinforced) < mean_strain(&uniform));
+    }
+
+    #[test]
+    fn flat_perimeter_matches_grid_size() {
+        let (width, height, spacing) = (5, 4, 10);
+        let cloth = Cloth::new(width, height, spacing, 0, 0, 1.0);
+        let expected = 2.0 * ((width - 1) + (height - 1)) as f64 * spacing as f64;
+        assert!((cloth.perimeter() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cutting_a_column_splits_the_cloth() {
+        let mut cloth = Cloth::new(4, 3, 10, 0, 0, 1.0);
+        assert_eq!(cloth.connected_components(), 1);
+
+        // Tear the sticks between the second and third column in every row
+        for i in 0..cloth.sticks.len() {
+            let pair = (cloth.sticks[i].p1 % 4, cloth.sticks[i].p2 % 4);
+            if pair == (2, 1) {
+                cloth.tear_stick(i);
+            }
+        }
+        assert_eq!(cloth.connected_components(), 2);
+    }
+
+    #[test]
+    fn drag_strength_scales_displacement() {
+        let drag = |strength: f64| {
+            let mut cloth = Cloth::unpinned(1, 1, 10, 0, 0, 7.0);
+            cloth.set_gravity(Vector2::ZERO);
+            cloth.set_drag_strength(strength);
+            let mouse = mouse_at(5.0, 0.0, &[MouseButton::Left]);
+            cloth.update(DT, &mouse, Vector2::ZERO);
+            cloth.positions[0].x
+        };
+        let (weak, strong) = (drag(1000.0), drag(2000.0));
+        assert!(weak > 0.0);
+        assert!((strong - 2.0 * weak).abs() < 1e-9);
+    }
+
+    #[test]
+    fn over_relaxation_reduces_sag() {
+        let sag = |factor: f64| {
+            let mut cloth = Cloth::new(8, 8, 10, 0, 0, f64::INFINITY);
+            cloth.set_over_relaxation(factor);
+            cloth.set_solver_iterations(4, 0.0);
+            run(&mut cloth, 30);
+            cloth.lowest_point().unwrap().1.y - 70.0
+        };
+        let (plain, relaxed) = (sag(1.0), sag(1.5));
+        assert!(relaxed > 0.0);
+        assert!(relaxed < plain);
+    }
+
+    #[test]
+    fn most_stressed_stick_is_the_most_stretched_intact_one() {
+        let mut cloth = Cloth::new(6, 6, 10, 0, 0, f64::INFINITY);
+        run(&mut cloth, 30);
+
+        let most = cloth.most_stressed_stick().unwrap();
+        let stretch = cloth.sticks[most].stretch(&cloth.positions);
+        assert!(stretch > 1.0);
+        assert!(cloth
+            .sticks
+            .iter()
+            .all(|stick| stick.stretch(&cloth.positions) <= stretch));
+
+        // A torn stick waiting to be removed is no longer a candidate
+        cloth.tear_stick(most);
+        let next = cloth.most_stressed_stick().unwrap();
+        assert_ne!(next, most);
+        assert!(cloth.sticks[next].stretch(&cloth.positions) <= stretch);
+    }
+
+    #[test]
+    fn sample_interpolates_inside_a_quad() {
+        let cloth = Cloth::new(2, 2, 10, 0, 0, 1.0);
+        assert!(close(
+            cloth.sample(0.5, 0.5).unwrap(),
+            Vector2::new(5.0, 5.0)
+        ));
+    }
+
+    #[test]
+    fn adding_points_drops_the_grid_layout() {
+        let mut inserted = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        inserted.insert_point(Vector2::new(50.0, 50.0), false);
+        let mut merged = Cloth::new(3, 2, 10, 0, 0, 1.0);
+        merged.merge(Cloth::new(2, 2, 10, 100, 0, 1.0));
+
+        for cloth in [inserted, merged] {
+            assert!(cloth.sample(0.5, 0.5).is_none());
+            let normals = cloth.compute_normals();
+            assert_eq!(normals.len(), cloth.len_points());
+            assert!(normals.iter().all(|normal| normal.magnitude() == 0.0));
+            assert!(cloth.curvature_map().iter().all(|&k| k == 0.0));
+        }
+    }
+
+    #[test]
+    fn middle_click_toggles_one_pin() {
+        let mut cloth = Cloth::unpinned(3, 3, 10, 0, 0, 1.0);
+        let pressed = mouse_at(11.0, 9.0, &[MouseButton::Middle]);
+        let released = mouse_at(11.0, 9.0, &[]);
+        let pinned = |cloth: &Cloth| {
+            (0..cloth.len_points())
+                .filter(|&i| cloth.pinned[i])
+                .collect::<Vec<_>>()
+        };
+
+        cloth.update(DT, &pressed, Vector2::new(11.0, 9.0));
+        assert_eq!(pinned(&cloth), [4]);
+
+        // Holding the button doesn't toggle again, pressing it again does
+        cloth.update(DT, &pressed, Vector2::new(11.0, 9.0));
+        assert_eq!(pinned(&cloth), [4]);
+        cloth.update(DT, &released, Vector2::new(11.0, 9.0));
+        cloth.update(DT, &pressed, Vector2::new(11.0, 9.0));
+        assert!(pinned(&cloth).is_empty());
+    }
+
+    #[test]
+    fn cracks_run_in_connected_lines() {
+        let tear = |propagate: bool| {
+            // Without stiffness the cloth holds still, stretched evenly to just short of its
+            // elasticity, so only the stick pulled further snaps by itself
+            let mut cloth = Cloth::unpinned(9, 9, 10, 0, 0, 0.1);
+            cloth.transform(|p| p * 1.09);
+            cloth.set_gravity(Vector2::ZERO);
+            cloth.set_stiffness(0.0);
+            cloth.set_crack_propagation(propagate, 2);
+            cloth.positions[40].x += 1.0;
+            cloth.prev_positions[40].x += 1.0;
+            run(&mut cloth, 6);
+            cloth.tear_history.into_iter().collect::<Vec<_>>()
+        };
+
+        assert_eq!(tear(false).len(), 1);
+
+        // Every torn stick touches another one, so the tears form a single line
+        let torn = tear(true);
+        assert!(torn.len() > 2);
+        let mut reached = vec![false; torn.len()];
+        let mut stack = vec![0];
+        reached[0] = true;
+        while let Some(i) = stack.pop() {
+            for j in 0..torn.len() {
+                let touching = [torn[j].p1, torn[j].p2]
+                    .iter()
+                    .any(|&p| p == torn[i].p1 || p == torn[i].p2);
+                if touching && !reached[j] {
+                    reached[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+        assert!(reached.iter().all(|&r| r));
+    }
+
+    #[test]
+    #[should_panic(expected = "stick cannot connect a point to itself")]
+    fn self_loop_stick_is_rejected() {
+        let points = [(Vector2::ZERO, true), (Vector2::new(10.0, 0.0), false)];
+        Cloth::from_parts(&points, &[(0, 1, 1.0), (1, 1, 1.0)]);
+    }
+
+    #[test]
+    fn holding_right_click_cuts_once() {
+        let cut = |continuous: bool| {
+            let mut cloth = Cloth::new(7, 7, 10, 0, 0, f64::INFINITY);
+            cloth.set_continuous_cut(continuous);
+            let mouse = mouse_at(30.0, 30.0, &[MouseButton::Right]);
+
+            // Torn sticks are removed on the update after the cut
+            cloth.update(DT, &mouse, Vector2::new(30.0, 30.0));
+            cloth.update(DT, &mouse, Vector2::new(30.0, 30.0));
+            let after_click = cloth.len_sticks();
+            // The cloth sags through the cursor while the button stays held
+            for _ in 0..60 {
+                cloth.update(DT, &mouse, Vector2::new(30.0, 30.0));
+            }
+            (after_click, cloth.len_sticks())
+        };
+
+        let (after_click, after_hold) = cut(false);
+        assert!(after_click < 2 * 7 * 6);
+        assert_eq!(after_hold, after_click);
+        let (after_click, after_hold) = cut(true);
+        assert!(after_hold < after_click);
+    }
+
+    #[test]
+    fn boundary_sticks_run_around_the_edge() {
+        let mut cloth = Cloth::new(4, 3, 10, 0, 0, 1.0);
+        let on_edge = |cloth: &Cloth, i: usize| {
+            let [a, b] = [cloth.sticks[i].p1, cloth.sticks[i].p2].map(|p| (p % 4, p / 4));
+            (a.1 == b.1 && (a.1 == 0 || a.1 == 2)) || (a.0 == b.0 && (a.0 == 0 || a.0 == 3))
+        };
+        let expected: Vec<usize> = (0..cloth.len_sticks())
+            .filter(|&i| on_edge(&cloth, i))
+            .collect();
+        assert_eq!(expected.len(), 2 * (3 + 2));
+        assert_eq!(cloth.boundary_sticks(), expected);
+
+        // Tearing the stick between the two middle points opens a hole before the next update
+        let middle = cloth
+            .stick_pairs()
+            .iter()
+            .position(|&pair| pair == (6, 5))
+            .unwrap();
+        let perimeter = cloth.perimeter();
+        cloth.tear_stick(middle);
+        assert!(!cloth.boundary_sticks().contains(&middle));
+        assert!((cloth.perimeter() - perimeter - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hand_built_triangle_swings_down_in_one_piece() {
+        let points = [
+            (Vector2::new(0.0, 0.0), true),
+            (Vector2::new(30.0, 0.0), false),
+            (Vector2::new(15.0, 20.0), false),
+        ];
+        let mut cloth = Cloth::from_parts(&points, &[(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0)]);
+        assert_eq!(cloth.stick_pairs(), [(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(cloth.sticks[0].length, 30.0);
+        assert_eq!(cloth.sticks[1].length, 25.0);
+
+        run(&mut cloth, 300);
+        assert_eq!(cloth.len_sticks(), 3);
+        assert!(close(cloth.anchor_positions()[0], Vector2::ZERO));
+        // Hanging from its pin, the triangle keeps its shape with the free corners below the pin
+        for stick in &cloth.sticks {
+            assert!(stick.strain(&cloth.positions).abs() < 0.05);
+        }
+        assert!(cloth.positions[1].y > 0.0 && cloth.positions[2].y > 0.0);
+    }
+
+    #[test]
+    fn flat_cloth_normals_point_up() {
+        // Up the screen is towards negative y
+        let cloth = Cloth::new(4, 3, 10, 0, 0, 1.0);
+        let normals = cloth.compute_normals();
+        assert_eq!(normals.len(), 12);
+        assert!(normals
+            .iter()
+            .all(|&normal| close(normal, Vector2::new(0.0, -1.0))));
+    }
+
+    #[test]
+    fn unpinned_cloth_falls_entirely() {
+        let mut cloth = Cloth::unpinned(5, 5, 10, 0, 0, 1.0);
+        assert!(cloth.pinned.iter().all(|&pinned| !pinned));
+        assert!(cloth.anchor_positions().is_empty());
+
+        run(&mut cloth, 30);
+        for (position, initial) in cloth.positions.iter().zip(&cloth.initial_positions) {
+            assert!(position.y > initial.y + 10.0);
+        }
+    }
+
+    #[test]
+    fn more_iterations_reduce_length_error() {
+        let error = |iterations: usize| {
+            let mut cloth = Cloth::new(8, 8, 10, 0, 0, f64::INFINITY);
+            cloth.set_solver_iterations(iterations, 0.0);
+            run(&mut cloth, 30);
+            cloth.rms_length_error()
+        };
+        assert_eq!(Cloth::new(8, 8, 10, 0, 0, 1.0).rms_length_error(), 0.0);
+        let errors = [error(1), error(4), error(16)];
+        assert!(errors[0] > errors[1] && errors[1] > errors[2]);
+    }
+
+    #[test]
+    fn gravity_well_pulls_points_in() {
+        let mut cloth = Cloth::unpinned(1, 1, 10, 0, 0, 1.0);
+        cloth.set_gravity(Vector2::ZERO);
+        cloth.add_gravity_well(Vector2::new(50.0, 0.0), 1e6);
+
+        run(&mut cloth, 1);
+        let first = cloth.velocity(0);
+        run(&mut cloth, 1);
+        let second = cloth.velocity(0);
+        assert!(first.x > 0.0 && second.x > first.x);
+        assert!(cloth.positions[0].y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn time_scale_shortens_the_step() {
+        let mouse = Mouse::default();
+        let mut slowed = Cloth::new(4, 4, 10, 0, 0, 1.0);
+        slowed.set_time_scale(0.5);
+        let mut halved = Cloth::new(4, 4, 10, 0, 0, 1.0);
+        for _ in 0..10 {
+            slowed.update(DT, &mouse, Vector2::ZERO);
+            halved.update(DT / 2.0, &mouse, Vector2::ZERO);
+        }
+
+        assert!((slowed.elapsed() - 10.0 * DT / 2.0).abs() < 1e-12);
+        for (a, b) in slowed.positions.iter().zip(&halved.positions) {
+            assert!(close(*a, *b));
+        }
+    }
+
+    #[test]
+    fn cloths_start_with_default_gravity() {
+        assert!(close(DEFAULT_GRAVITY, Vector2::new(0.0, 981.0)));
+        for cloth in [
+            Cloth::new(3, 3, 10, 0, 0, 1.0),
+            Cloth::unpinned(3, 3, 10, 0, 0, 1.0),
+            Cloth::from_parts(&[(Vector2::ZERO, false)], &[]),
+        ] {
+            assert!(close(cloth.gravity(), DEFAULT_GRAVITY));
+        }
+    }
+
+    #[test]
+    fn loaded_sticks_lead_the_stress_list() {
+        // Pull the last column of the bottom two rows to the right
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, f64::INFINITY);
+        for i in [19, 24] {
+            cloth.positions[i].x += 4.0;
+        }
+        let loaded = |&(i, _): &(usize, f64)| {
+            let stick = &cloth.sticks[i];
+            [19, 24].contains(&stick.p1) || [19, 24].contains(&stick.p2)
+        };
+
+        let by_stress = cloth.sticks_by_stress();
+        assert_eq!(by_stress.len(), cloth.len_sticks());
+        assert!(by_stress.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(by_stress[..3].iter().all(loaded));
+        assert!(by_stress[3..]
+            .iter()
+            .all(|&(_, strain)| strain.abs() < 1e-9));
+
+        // Torn sticks are left out
+        cloth.tear_stick(by_stress[0].0);
+        let after_tear = cloth.sticks_by_stress();
+        assert_eq!(after_tear.len(), by_stress.len() - 1);
+        assert_eq!(after_tear[0].0, by_stress[1].0);
+    }
+
+    #[test]
+    fn stick_pairs_follow_the_grid() {
+        let cloth = Cloth::new(3, 2, 10, 0, 0, 1.0);
+        // Each point links left, then up
+        let expected = [(1, 0), (2, 1), (3, 0), (4, 3), (4, 1), (5, 4), (5, 2)];
+        assert_eq!(cloth.stick_pairs(), expected);
+    }
+
+    #[test]
+    fn snapshot_diff_measures_one_step() {
+        let mut cloth = Cloth::unpinned(4, 4, 10, 0, 0, f64::INFINITY);
+        let before = cloth.snapshot();
+        run(&mut cloth, 1);
+        let after = cloth.snapshot();
+
+        // A free fall of one step moves every point by gravity * dt^2
+        let fall = DEFAULT_GRAVITY.y * DT * DT;
+        let moved = snapshot::diff(&before, &after);
+        assert!(moved > 0.0);
+        assert!(moved <= fall + 1e-9);
+        assert_eq!(snapshot::diff(&after, &after), 0.0);
+    }
+
+    #[test]
+    fn sticks_store_indices_in_construction_order() {
+        let width = 5;
+        let cloth = Cloth::new(width, 4, 10, 0, 0, 1.0);
+
+        // Sticks are made point by point, each linking back to its left or upper neighbour
+        for pair in cloth.sticks.windows(2) {
+            assert!(pair[0].p1 <= pair[1].p1);
+        }
+        for stick in &cloth.sticks {
+            assert!(stick.p2 == stick.p1 - 1 || stick.p2 == stick.p1 - width as usize);
+            let length = cloth.positions[stick.p1].distance(&cloth.positions[stick.p2]);
+            assert!((length - 10.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn fire_spreads_to_adjacent_sticks() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, f64::INFINITY);
+        cloth.set_gravity(Vector2::ZERO);
+        cloth.set_burn_rate(2.5 * DT, 100.0);
+        let burning = |cloth: &Cloth| -> Vec<usize> {
+            (0..cloth.sticks.len())
+                .filter(|&i| cloth.sticks[i].burning.is_some())
+                .collect()
+        };
+
+        // Stick 0 links points 1 and 0
+        cloth.ignite(0);
+        run(&mut cloth, 2);
+        assert_eq!(burning(&cloth), [0]);
+
+        run(&mut cloth, 1);
+        assert_eq!(burning(&cloth), [0, 1, 2, 4]);
+
+        // The sticks lit by the first ones spread the fire a step later
+        run(&mut cloth, 3);
+        assert!(burning(&cloth).len() > 4);
+        assert!(cloth.sticks.iter().all(|stick| !stick.broken));
+    }
+
+    #[test]
+    fn smoothing_lowers_velocity_variance() {
+        let jittery = |smoothing: f64| {
+            let mut cloth = Cloth::new(8, 8, 10, 0, 0, f64::INFINITY);
+            cloth.set_gravity(Vector2::ZERO);
+            cloth.set_smoothing(smoothing);
+            // Checkerboard pattern of small offsets, the worst case for a single iteration
+            for i in 0..cloth.positions.len() {
+                if !cloth.pinned[i] {
+                    let sign = if (i % 8 + i / 8) % 2 == 0 { 1.0 } else { -1.0 };
+                    cloth.positions[i] += Vector2::new(sign, sign);
+                }
+            }
+            run(&mut cloth, 5);
+
+            let velocities: Vec<Vector2> = (0..cloth.positions.len())
+                .map(|i| cloth.velocity(i))
+                .collect();
+            let mean =
+                velocities.iter().fold(Vector2::ZERO, |sum, &v| sum + v) / velocities.len() as f64;
+            velocities
+                .iter()
+                .map(|v| v.distance(&mean).powi(2))
+                .sum::<f64>()
+                / velocities.len() as f64
+        };
+
+        assert!(jittery(0.5) < jittery(0.0));
+    }
+
+    #[test]
+    fn stress_color_clamps_to_the_top_color() {
+        let top = stress_color(0.2, 0.2);
+        assert_eq!((top.r, top.g, top.b), (1.0, 0.0, 0.0));
+        for strain in [0.3, 1.5, 100.0] {
+            let color = stress_color(strain, 0.2);
+            assert_eq!((color.r, color.g, color.b), (top.r, top.g, top.b));
+        }
+
+        let half = stress_color(0.1, 0.2);
+        assert!((half.g - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn one_way_sticks_only_resist_stretching() {
+        let points = [
+            (Vector2::new(0.0, 0.0), false),
+            (Vector2::new(10.0, 0.0), false),
+        ];
+        let mut rope =
+            Cloth::from_parts(&points, &[(0, 1, f64::INFINITY)]).with_one_way_sticks(true);
+        rope.set_gravity(Vector2::ZERO);
+        let length = |rope: &Cloth| rope.positions[0].distance(&rope.positions[1]);
+
+        rope.positions[1] = Vector2::new(5.0, 0.0);
+        rope.prev_positions[1] = rope.positions[1];
+        run(&mut rope, 1);
+        assert!((length(&rope) - 5.0).abs() < 1e-9);
+
+        rope.positions[1] = Vector2::new(20.0, 0.0);
+        rope.prev_positions[1] = rope.positions[1];
+        run(&mut rope, 1);
+        assert!((length(&rope) - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn depth_alpha_stays_in_range() {
+        assert_eq!(depth_alpha(-3.0), 0.0);
+        assert_eq!(depth_alpha(0.0), 0.0);
+        assert_eq!(depth_alpha(0.25), 0.25);
+        assert_eq!(depth_alpha(1.0), 1.0);
+        assert_eq!(depth_alpha(7.5), 1.0);
+    }
+
+    #[test]
+    fn sample_grid_hits_centers_and_corners() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, f64::INFINITY);
+        assert!(close(cloth.sample_grid(0.0, 0.0), Vector2::new(0.0, 0.0)));
+        assert!(close(cloth.sample_grid(2.0, 2.0), Vector2::new(20.0, 20.0)));
+        assert!(close(cloth.sample_grid(2.0, 0.0), Vector2::new(20.0, 0.0)));
+        assert!(close(cloth.sample_grid(0.5, 0.5), Vector2::new(5.0, 5.0)));
+        assert!(close(cloth.sample_grid(1.5, 0.5), Vector2::new(15.0, 5.0)));
+        // Out of range coordinates clamp to the nearest edge
+        assert!(close(cloth.sample_grid(-4.0, 9.0), Vector2::new(0.0, 20.0)));
+
+        // Unlike `sample`, tears are interpolated across
+        cloth.tear_stick(0);
+        assert!(cloth.sample(0.25, 0.25).is_none());
+        assert!(close(cloth.sample_grid(0.5, 0.5), Vector2::new(5.0, 5.0)));
+    }
+
+    #[test]
+    fn break_margin_ignores_flicker_at_the_threshold() {
+        let points = [
+            (Vector2::new(0.0, 0.0), true),
+            (Vector2::new(10.0, 0.0), false),
+        ];
+        let mut cloth = Cloth::from_parts(&points, &[(0, 1, 0.1)]).with_break_margin(0.01);
+        cloth.set_gravity(Vector2::ZERO);
+        let place = |cloth: &mut Cloth, x: f64| {
+            cloth.positions[1] = Vector2::new(x, 0.0);
+            cloth.prev_positions[1] = cloth.positions[1];
+            run(cloth, 1);
+        };
+
+        // Hovering around a stretch of 0.1, just past the elasticity but within the margin
+        for step in 0..20 {
+            place(&mut cloth, if step % 2 == 0 { 11.05 } else { 10.95 });
+        }
+        assert_eq!(cloth.len_sticks(), 1);
+
+        place(&mut cloth, 11.2);
+        assert!(cloth.sticks[0].broken);
+    }
+
+    #[test]
+    fn undoing_a_tear_restores_the_stick() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, f64::INFINITY);
+        let pairs = cloth.stick_pairs();
+
+        cloth.tear_stick(4);
+        run(&mut cloth, 1);
+        assert_eq!(cloth.len_sticks(), pairs.len() - 1);
+
+        assert!(cloth.undo_tear());
+        assert_eq!(cloth.len_sticks(), pairs.len());
+        let mut restored = cloth.stick_pairs();
+        restored.sort_unstable();
+        let mut expected = pairs.clone();
+        expected.sort_unstable();
+        assert_eq!(restored, expected);
+        assert!(!cloth.undo_tear());
+
+        // A restored stick waits out the break delay again before breaking
+        let points = [
+            (Vector2::new(0.0, 0.0), false),
+            (Vector2::new(10.0, 0.0), false),
+        ];
+        let mut cloth = Cloth::from_parts(&points, &[(0, 1, 0.1)]).with_break_delay(3);
+        cloth.set_gravity(Vector2::ZERO);
+        cloth.set_stiffness(0.0);
+        cloth.positions[1].x = 12.0;
+        cloth.prev_positions[1].x = 12.0;
+        cloth.sticks[0].selected = true;
+        run(&mut cloth, 5);
+        assert_eq!(cloth.len_sticks(), 0);
+
+        assert!(cloth.undo_tear());
+        assert!(!cloth.sticks[0].selected);
+        run(&mut cloth, 3);
+        assert!(!cloth.sticks[0].broken);
+        run(&mut cloth, 1);
+        assert!(cloth.sticks[0].broken);
+    }
+
+    #[test]
+    fn relaxing_settles_stretched_sticks() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, f64::INFINITY);
+        // Stretch the cloth downwards from its pinned top row
+        for position in &mut cloth.positions {
+            position.y *= 1.3;
+        }
+        cloth.prev_positions.clone_from(&cloth.positions);
+        let pins: Vec<Vector2> = (0..cloth.positions.len())
+            .filter(|&i| cloth.pinned[i])
+            .map(|i| cloth.positions[i])
+            .collect();
+        let error = |cloth: &Cloth| -> f64 {
+            cloth
+                .sticks
+                .iter()
+                .map(|stick| stick.strain(&cloth.positions).abs())
+                .sum()
+        };
+        let before = error(&cloth);
+
+        cloth.relax(20);
+        assert!(error(&cloth) < before * 0.5);
+        let after: Vec<Vector2> = (0..cloth.positions.len())
+            .filter(|&i| cloth.pinned[i])
+            .map(|i| cloth.positions[i])
+            .collect();
+        assert!(pins.iter().zip(&after).all(|(&a, &b)| close(a, b)));
+        // Relaxing moves points without giving them any velocity
+        assert!((0..cloth.positions.len()).all(|i| close(cloth.velocity(i), Vector2::ZERO)));
+    }
+
+    #[test]
+    fn written_verlet_state_sets_the_velocity() {
+        let mut cloth = Cloth::unpinned(3, 3, 10, 0, 0, f64::INFINITY);
+        cloth.set_gravity(Vector2::ZERO);
+        let mut state = cloth.verlet_state();
+        assert!(state.iter().all(|&(position, prev)| close(position, prev)));
+
+        // Shifting every previous position left gives the whole cloth the same rightward velocity
+        for (_, prev) in &mut state {
+            *prev -= Vector2::new(2.0, 0.0);
+        }
+        cloth.set_verlet_state(&state);
+        run(&mut cloth, 1);
+
+        let moved = Vector2::new(2.0 * (1.0 - cloth.drag()), 0.0);
+        for (i, &(position, _)) in state.iter().enumerate() {
+            assert!(close(cloth.positions[i] - position, moved));
+        }
+    }
+
+    #[test]
+    fn torn_cloth_splits_into_fragments() {
+        let mut cloth = Cloth::new(4, 3, 10, 0, 0, 1.0);
+        // Tear off the first column
+        for i in 0..cloth.sticks.len() {
+            let pair = (cloth.sticks[i].p1 % 4, cloth.sticks[i].p2 % 4);
+            if pair == (1, 0) {
+                cloth.tear_stick(i);
+            }
+        }
+        let pinned = cloth.pinned.iter().filter(|&&pinned| pinned).count();
+
+        let fragments = cloth.split_into_fragments();
+        assert_eq!(fragments.len(), 2);
+        let mut sizes: Vec<(usize, usize)> = fragments
+            .iter()
+            .map(|fragment| (fragment.len_points(), fragment.len_sticks()))
+            .collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [(3, 2), (9, 12)]);
+        let split_pinned: usize = fragments
+            .iter()
+            .map(|fragment| fragment.pinned.iter().filter(|&&pinned| pinned).count())
+            .sum();
+        assert_eq!(split_pinned, pinned);
+    }
+
+    #[test]
+    fn unbreakable_cloth_holds_and_stays_draggable() {
+        let points = [
+            (Vector2::new(0.0, 0.0), true),
+            (Vector2::new(10.0, 0.0), false),
+        ];
+        let mut cloth = Cloth::from_parts(&points, &[(0, 1, f64::INFINITY)]);
+        cloth.set_gravity(Vector2::ZERO);
+        cloth.positions[1] = Vector2::new(1e6, 0.0);
+        run(&mut cloth, 1);
+        assert!(!cloth.sticks[0].broken);
+        assert_eq!(cloth.len_sticks(), 1);
+
+        // A finite drag limit and stress color range stand in for the missing elasticity
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, f64::INFINITY);
+        let mouse = mouse_at(10.0, 10.0, &[MouseButton::Left]);
+        cloth.update(DT, &mouse, Vector2::new(-1000.0, 10.0));
+        let force = cloth.last_drag_force();
+        assert!(force.x.is_finite() && force.x > 0.0);
+        assert!((force.x - UNBREAKABLE_DRAG_LIMIT * FORCE_MULTIPLIER).abs() < 1e-9);
+        assert_eq!(cloth.stress_color_max, UNBREAKABLE_STRESS_COLOR_MAX);
+        assert_eq!(stress_color(1.0, cloth.stress_color_max).g, 0.0);
+    }
+
+    #[test]
+    fn reset_restarts_the_step_count_and_returns_to_the_grid() {
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, f64::INFINITY);
+        let grid = cloth.positions.clone();
+        run(&mut cloth, 7);
+        assert_eq!(cloth.step_count(), 7);
+
+        // Pin a fallen point where it hangs and drag every pin to the side
+        cloth.toggle_pin(15);
+        cloth.set_anchors_follow_mouse(true);
+        let mouse = mouse_at(50.0, 0.0, &[]);
+        cloth.update(DT, &mouse, Vector2::new(20.0, 0.0));
+        assert_eq!(cloth.step_count(), 8);
+        assert!(cloth
+            .anchor_positions()
+            .iter()
+            .all(|anchor| anchor.x >= 30.0));
+
+        cloth.reset();
+        assert_eq!(cloth.step_count(), 0);
+        assert!(cloth
+            .positions
+            .iter()
+            .zip(&grid)
+            .all(|(&a, &b)| close(a, b)));
+        let pinned: Vec<Vector2> = (0..grid.len())
+            .filter(|&i| cloth.pinned[i])
+            .map(|i| grid[i])
+            .collect();
+        assert!(cloth
+            .anchor_positions()
+            .iter()
+            .zip(&pinned)
+            .all(|(&a, &b)| close(a, b)));
+    }
+
+    #[test]
+    fn symmetric_motion_has_no_momentum() {
+        let mut cloth = Cloth::unpinned(4, 4, 10, 0, 0, f64::INFINITY);
+        assert!(close(cloth.total_momentum(), Vector2::ZERO));
+
+        // Spread the cloth out from its middle column, mirrored on either side
+        cloth.set_gravity(Vector2::ZERO);
+        for i in 0..cloth.positions.len() {
+            let offset = cloth.positions[i].x - 15.0;
+            cloth.prev_positions[i].x -= offset * 0.1;
+        }
+        assert!(close(cloth.total_momentum(), Vector2::ZERO));
+        run(&mut cloth, 1);
+        assert!(cloth.total_momentum().magnitude() < 1e-9);
+
+        // Moving everything one way does carry momentum, from the unpinned points only
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, f64::INFINITY);
+        for i in 0..cloth.positions.len() {
+            cloth.prev_positions[i] = cloth.positions[i] - Vector2::new(1.0, 0.0);
+        }
+        let unpinned = cloth.pinned.iter().filter(|&&pinned| !pinned).count();
+        assert!(close(
+            cloth.total_momentum(),
+            Vector2::new(unpinned as f64, 0.0)
+        ));
+    }
+
+    #[test]
+    fn region_wind_only_pushes_points_inside() {
+        // Loose points, so the sticks don't drag the points outside along
+        let points: Vec<(Vector2, bool)> = (0..4)
+            .map(|i| (Vector2::new(i as f64 * 10.0, 0.0), false))
+            .collect();
+        let mut cloth = Cloth::from_parts(&points, &[]);
+        cloth.set_gravity(Vector2::ZERO);
+        cloth.apply_wind_region(
+            (Vector2::new(-5.0, -5.0), Vector2::new(15.0, 5.0)),
+            Vector2::new(0.0, 500.0),
+        );
+        let start = cloth.positions.clone();
+        run(&mut cloth, 1);
+
+        let moved: Vec<bool> = (0..4).map(|i| cloth.positions[i].y > start[i].y).collect();
+        assert_eq!(moved, [true, true, false, false]);
+
+        // The wind only lasts for the update after it was applied
+        let now = cloth.positions.clone();
+        let velocity = cloth.positions[0] - start[0];
+        run(&mut cloth, 1);
+        assert!(cloth.positions[0].y - now[0].y <= velocity.y);
+    }
+
+    #[test]
+    fn hanging_cloth_follows_rotating_gravity() {
+        let points = [
+            (Vector2::new(0.0, 0.0), true),
+            (Vector2::new(0.0, 20.0), false),
+        ];
+        let mut pendulum = Cloth::from_parts(&points, &[(0, 1, f64::INFINITY)]);
+        let mouse = Mouse::default();
+
+        // Turn gravity a quarter of the way round at a time, letting it settle at each step
+        for quarter in 1..=4 {
+            let angle = std::f64::consts::PI / 2.0 * (1.0 + quarter as f64 * 0.25);
+            let gravity = Vector2::new(angle.cos(), angle.sin()) * 981.0;
+            for _ in 0..600 {
+                pendulum.update_with_gravity(DT, &mouse, Vector2::ZERO, gravity);
+            }
+            assert!(close(pendulum.gravity(), gravity));
+            let hanging = (pendulum.positions[1] - pendulum.positions[0]).normalized();
+            assert!(hanging.dot(&gravity.normalized()) > 0.99);
+        }
+    }
+
+    #[test]
+    fn post_iteration_hook_runs_after_every_iteration() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, f64::INFINITY);
+        cloth.set_solver_iterations(4, 0.0);
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        cloth.set_post_iteration(Box::new(move |points: &mut [PointView]| {
+            counter.set(counter.get() + 1);
+            points[5].position.y = 3.0;
+        }));
+
+        run(&mut cloth, 3);
+        assert_eq!(calls.get(), 3 * 4);
+        // The hook has the last word, the sticks are solved before it
+        assert_eq!(cloth.positions[5].y, 3.0);
+        assert!(cloth.positions[9].y > 3.0);
+    }
+
+    #[test]
+    fn getters_return_the_settings() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 2.5);
+        assert_eq!(cloth.elasticity(), 2.5);
+        assert_eq!(cloth.drag(), 0.05);
+
+        cloth.set_gravity(Vector2::new(3.0, -4.0));
+        assert!(close(cloth.gravity(), Vector2::new(3.0, -4.0)));
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn frame_timings_add_up_to_the_total() {
+        let mut cloth = Cloth::new(30, 30, 10, 0, 0, 1.0);
+        run(&mut cloth, 5);
+        let timings = cloth.last_timings();
+        let phases = timings.integration + timings.constraints;
+        assert!(timings.total > std::time::Duration::ZERO);
+        assert_eq!(phases, timings.total);
+    }
+
+    #[test]
+    fn baked_shape_needs_no_correction() {
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, f64::INFINITY);
+        cloth.set_gravity(Vector2::ZERO);
+        // Drag a corner out while paused, leaving the cloth at rest in a stretched shape
+        cloth.positions[15] += Vector2::new(12.0, 7.0);
+        cloth.prev_positions[15] = cloth.positions[15];
+        cloth.bake_rest_lengths();
+
+        let shape = cloth.positions.clone();
+        run(&mut cloth, 1);
+        assert!(cloth
+            .positions
+            .iter()
+            .zip(&shape)
+            .all(|(&a, &b)| close(a, b)));
+    }
+
+    #[test]
+    fn point_lands_on_a_tent() {
+        let mut cloth = Cloth::from_parts(&[(Vector2::new(20.0, 0.0), false)], &[]);
+        let tent = vec![
+            Vector2::new(-60.0, 60.0),
+            Vector2::new(0.0, 20.0),
+            Vector2::new(60.0, 60.0),
+        ];
+        cloth.add_polyline_obstacle(tent.clone());
+        let surface = |position: Vector2| {
+            point_segment_distance(position, tent[0], tent[1])
+                .min(point_segment_distance(position, tent[1], tent[2]))
+        };
+
+        run(&mut cloth, 20);
+        let landed = cloth.positions[0];
+        assert!(surface(landed) < 1e-9);
+        // With nothing to hold it, the point slides down the slope without sinking into it
+        for _ in 0..20 {
+            run(&mut cloth, 1);
+            assert!(surface(cloth.positions[0]) < 1e-9);
+        }
+        assert!(cloth.positions[0].x > landed.x && cloth.positions[0].y > landed.y);
+    }
+
+    #[test]
+    fn sweeping_pushes_points_along_the_path() {
+        let points: Vec<(Vector2, bool)> = (0..5)
+            .map(|i| (Vector2::new(i as f64 * 10.0, 0.0), false))
+            .collect();
+        let mut cloth = Cloth::from_parts(&points, &[]);
+        cloth.set_gravity(Vector2::ZERO);
+        // Sweep rightwards along the row, passing the first three points
+        cloth.sweep(Vector2::new(-5.0, 2.0), Vector2::new(25.0, 2.0), 5.0, 500.0);
+        let start = cloth.positions.clone();
+        run(&mut cloth, 1);
+
+        for (i, (&position, &start)) in cloth.positions.iter().zip(&start).enumerate() {
+            let moved = position - start;
+            if i < 3 {
+                assert!(moved.x > 0.0 && moved.y.abs() < 1e-9);
+            } else {
+                assert!(close(moved, Vector2::ZERO));
+            }
+        }
+    }
+
+    #[test]
+    fn curvature_spikes_at_a_fold() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, f64::INFINITY);
+        assert!(cloth
+            .curvature_map()
+            .iter()
+            .all(|&curvature| curvature.abs() < 1e-9));
+
+        // Fold the right of the cloth back over itself along the middle column
+        cloth.transform(|p| {
+            if p.x > 20.0 {
+                Vector2::new(40.0 - p.x, p.y + 1.0)
+            } else {
+                p
+            }
+        });
+        let curvature = cloth.curvature_map();
+        for (i, &curvature) in curvature.iter().enumerate() {
+            let (x, y) = (i % 5, i / 5);
+            if x == 2 {
+                assert!(curvature > 3.0);
+            } else {
+                assert!(curvature < 0.2, "point ({x}, {y}) bends by {curvature}");
+            }
+        }
+    }
+
+    #[test]
+    fn weak_region_tears_first() {
+        let mut cloth = Cloth::unpinned(6, 6, 10, 0, 0, 0.5);
+        cloth.set_gravity(Vector2::ZERO);
+        // Sticks only break, without pulling the cloth back together
+        cloth.set_stiffness(0.0);
+        let (min, max) = (Vector2::new(0.0, 0.0), Vector2::new(25.0, 25.0));
+        cloth.set_region_elasticity(min, max, 0.1);
+        let inside = |cloth: &Cloth, stick: &Stick| {
+            let midpoint = (cloth.positions[stick.p1] + cloth.positions[stick.p2]) / 2.0;
+            // Midpoints as they were before the cloth was stretched
+            let midpoint = midpoint / cloth.positions[1].x * 10.0;
+            midpoint.x <= max.x + 1e-9 && midpoint.y <= max.y + 1e-9
+        };
+
+        // Stretch the whole cloth evenly a little more each update until something tears
+        let mut broken = Vec::new();
+        while broken.is_empty() {
+            cloth.transform(|p| p * 1.02);
+            run(&mut cloth, 1);
+            broken = cloth.sticks.iter().filter(|stick| stick.broken).collect();
+        }
+        assert!(broken.iter().all(|stick| inside(&cloth, stick)));
+        assert!(cloth.positions[1].x < 10.0 * 1.5);
+    }
+
+    #[test]
+    fn outline_of_a_pristine_grid_is_its_perimeter() {
+        let cloth = Cloth::new(5, 5, 10, 0, 0, 1.0);
+        let on_perimeter = |p: usize| {
+            let (x, y) = (p % 5, p / 5);
+            x == 0 || x == 4 || y == 0 || y == 4
+        };
+        let outline = cloth.boundary_sticks();
+        assert_eq!(outline.len(), 4 * 4);
+        for &i in &outline {
+            let stick = &cloth.sticks[i];
+            assert!(on_perimeter(stick.p1) && on_perimeter(stick.p2));
+        }
+
+        let mut draw = Draw::new(200, 200);
+        cloth.draw_outline(&mut draw, Color::WHITE);
+    }
+
+    #[test]
+    fn soft_pinned_point_bounces_then_settles() {
+        let mut cloth = Cloth::from_parts(&[(Vector2::new(30.0, 0.0), false)], &[]);
+        cloth.set_gravity(Vector2::ZERO);
+        cloth.soft_pin(0, Vector2::ZERO, 500.0);
+
+        let mut crossings = 0;
+        let mut side = cloth.positions[0].x.signum();
+        for _ in 0..120 {
+            run(&mut cloth, 1);
+            if cloth.positions[0].x.signum() != side {
+                side = -side;
+                crossings += 1;
+            }
+        }
+        // It overshoots the anchor back and forth instead of creeping up to it
+        assert!(crossings >= 2);
+
+        run(&mut cloth, 600);
+        assert!(cloth.positions[0].distance(&Vector2::ZERO) < 0.01);
+    }
+
+    #[test]
+    fn dash_count_follows_the_pattern() {
+        let (p1, p2) = (Vector2::ZERO, Vector2::new(30.0, 0.0));
+        // 4 on and 2 off fits five whole dashes into 30
+        assert_eq!(dash_segments(p1, p2, 4.0, 2.0).len(), 5);
+        // With 3 off the fifth dash starts at 28 and is cut short at the end
+        let dashes = dash_segments(p1, p2, 4.0, 3.0);
+        assert_eq!(dashes.len(), 5);
+        assert!(close(dashes[4].0, Vector2::new(28.0, 0.0)));
+        assert!(close(dashes[4].1, p2));
+
+        assert_eq!(dash_segments(p1, p2, 50.0, 1.0).len(), 1);
+        assert!(dash_segments(p1, p1, 4.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn settled_cloth_needs_fewer_iterations() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, f64::INFINITY);
+        // Gravity stretches the hanging cloth by a few percent every update, which the solver has
+        // to take out against the pins
+        cloth.set_solver_iterations(50, 0.05);
+        run(&mut cloth, 600);
+        let settled = cloth.last_iteration_count();
+
+        cloth.positions[24] += Vector2::new(40.0, 0.0);
+        run(&mut cloth, 1);
+        let perturbed = cloth.last_iteration_count();
+        assert!(settled < perturbed);
+        assert!(perturbed <= 50);
+    }
+
+    #[test]
+    fn weightless_point_stays_while_others_fall() {
+        let points: Vec<(Vector2, bool)> = (0..3)
+            .map(|i| (Vector2::new(i as f64 * 10.0, 0.0), false))
+            .collect();
+        let mut cloth = Cloth::from_parts(&points, &[]);
+        cloth.set_gravity_scale(1, 0.0);
+        cloth.set_gravity_scale(2, -1.0);
+        run(&mut cloth, 30);
+
+        assert!(cloth.positions[0].y > 10.0);
+        assert!(close(cloth.positions[1], Vector2::new(10.0, 0.0)));
+        assert!((cloth.positions[2].y + cloth.positions[0].y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn obstacles_on_other_layers_are_ignored() {
+        let fall = |mask: u32| {
+            let mut cloth = Cloth::from_parts(&[(Vector2::ZERO, false)], &[]);
+            let floor = vec![Vector2::new(-50.0, 20.0), Vector2::new(50.0, 20.0)];
+            cloth.add_polyline_obstacle_on_layer(floor, 0b10);
+            cloth.set_collision_mask(mask);
+            run(&mut cloth, 60);
+            cloth.positions[0].y
+        };
+
+        assert!((fall(0b10) - 20.0).abs() < 1e-9);
+        assert!((fall(0b11) - 20.0).abs() < 1e-9);
+        assert!(fall(0b01) > 100.0);
+    }
+
+    #[test]
+    fn vortex_spins_points_around_its_center() {
+        let center = Vector2::new(100.0, 100.0);
+        let spin = |strength: f64| {
+            let points: Vec<(Vector2, bool)> = (0..8)
+                .map(|i| {
+                    let angle = i as f64 * std::f64::consts::PI / 4.0;
+                    (
+                        center + Vector2::new(angle.cos(), angle.sin()) * 20.0,
+                        false,
+                    )
+                })
+                .chain([(center + Vector2::new(80.0, 0.0), false)])
+                .collect();
+            let mut cloth = Cloth::from_parts(&points, &[]);
+            cloth.set_gravity(Vector2::ZERO);
+            cloth.add_vortex(center, strength, 50.0);
+            run(&mut cloth, 1);
+
+            // Angular velocity of each ring point about the center, with y pointing down the screen
+            let ring: Vec<f64> = (0..8)
+                .map(|i| {
+                    let (r, v) = (cloth.positions[i] - center, cloth.velocity(i));
+                    (r.x * v.y - r.y * v.x) / r.magnitude_squared()
+                })
+                .collect();
+            (ring, cloth.velocity(8))
+        };
+
+        let (clockwise, outside) = spin(1000.0);
+        assert!(clockwise.iter().all(|&w| w > 0.0));
+        assert!(close(outside, Vector2::ZERO));
+        let (counterclockwise, _) = spin(-1000.0);
+        assert!(counterclockwise.iter().all(|&w| w < 0.0));
+    }
+
+    #[test]
+    fn lower_fractions_find_more_sticks_near_breaking() {
+        let mut cloth = Cloth::new(6, 6, 10, 0, 0, 1.0);
+        // Stretch the rows more the further right they go, up to a strain of 0.9
+        cloth.transform(|p| Vector2::new(p.x + p.x * p.x * 0.009, p.y));
+
+        let counts: Vec<usize> = [0.95, 0.8, 0.5, 0.2, 0.05]
+            .iter()
+            .map(|&fraction| cloth.sticks_near_breaking(fraction).len())
+            .collect();
+        assert_eq!(counts[0], 0);
+        assert!(counts.windows(2).all(|pair| pair[0] < pair[1]));
+        // The columns aren't stretched at all
+        assert!(cloth.sticks_near_breaking(0.0).len() <= 6 * 5);
+    }
+
+    #[test]
+    fn horizontal_cut_severs_one_row_of_sticks() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, 1.0);
+        // Two segments crossing the cloth between its second and third rows
+        cloth.cut_polyline(&[
+            Vector2::new(-5.0, 15.0),
+            Vector2::new(25.0, 14.0),
+            Vector2::new(45.0, 15.0),
+        ]);
+
+        let cut: Vec<(usize, usize)> = cloth
+            .sticks
+            .iter()
+            .filter(|stick| stick.broken)
+            .map(|stick| (stick.p1, stick.p2))
+            .collect();
+        let expected: Vec<(usize, usize)> = (10..15).map(|p| (p, p - 5)).collect();
+        assert_eq!(cut, expected);
+    }
+
+    #[test]
+    fn tiling_draws_every_stick_in_every_tile() {
+        let cloth = Cloth::new(4, 3, 10, 0, 0, 1.0);
+        let segments = cloth.tiled_segments(3, 2, Vector2::new(5.0, 5.0));
+        assert_eq!(segments.len(), cloth.len_sticks() * 3 * 2);
+
+        // The last tile is two tiles of 30 + 5 across and one of 20 + 5 down
+        let (_, (a, _)) = segments[segments.len() - cloth.len_sticks()];
+        assert!(close(a, cloth.positions[1] + Vector2::new(70.0, 25.0)));
+
+        let mut draw = Draw::new(200, 200);
+        cloth.draw_tiled(&mut draw, 3, 2, Vector2::ZERO);
+    }
+
+    #[test]
+    fn spring_grab_settles_on_the_cursor() {
+        let mut cloth = Cloth::from_parts(&[(Vector2::ZERO, false)], &[]);
+        cloth.set_gravity(Vector2::ZERO);
+        let stiffness: f64 = 400.0;
+        cloth.set_grab_mode(GrabMode::Spring {
+            stiffness,
+            damping: 2.0 * stiffness.sqrt(),
+        });
+
+        let cursor = Vector2::new(12.0, 0.0);
+        let mouse = mouse_at(cursor.x as f32, cursor.y as f32, &[MouseButton::Left]);
+        let mut furthest: f64 = 0.0;
+        for _ in 0..300 {
+            cloth.update(DT, &mouse, cursor);
+            furthest = furthest.max(cloth.positions[0].x);
+        }
+        assert!(cloth.positions[0].distance(&cursor) < 0.01);
+        assert!(furthest < cursor.x * 1.05);
+    }
+
+    #[test]
+    fn break_delay_survives_a_brief_overstretch() {
+        let points = [
+            (Vector2::new(0.0, 0.0), true),
+            (Vector2::new(10.0, 0.0), false),
+        ];
+        let mut cloth = Cloth::from_parts(&points, &[(0, 1, 0.1)]).with_break_delay(2);
+        cloth.set_gravity(Vector2::ZERO);
+        let stretch = |cloth: &mut Cloth, x: f64| {
+            cloth.positions[1] = Vector2::new(x, 0.0);
+            cloth.prev_positions[1] = cloth.positions[1];
+            run(cloth, 1);
+        };
+
+        // Overstretched for two updates at a time, then let go
+        for _ in 0..5 {
+            stretch(&mut cloth, 12.0);
+            stretch(&mut cloth, 12.0);
+            stretch(&mut cloth, 10.0);
+        }
+        assert!(!cloth.sticks[0].broken);
+
+        for _ in 0..3 {
+            stretch(&mut cloth, 12.0);
+        }
+        assert!(cloth.sticks[0].broken);
+    }
+
+    #[test]
+    fn only_grabbable_points_follow_the_mouse() {
+        let drag = |grabbable: &[usize], near: Vector2| {
+            // Two loose points far apart, once the torn stick is removed
+            let mut cloth = Cloth::unpinned(2, 1, 100, 0, 0, 7.0);
+            cloth.set_gravity(Vector2::ZERO);
+            cloth.tear_stick(0);
+            run(&mut cloth, 1);
+            let start = cloth.positions.clone();
+            cloth.set_grabbable(grabbable);
+            let mouse = mouse_at(near.x as f32, near.y as f32, &[MouseButton::Left]);
+            cloth.update(DT, &mouse, near - Vector2::new(5.0, 0.0));
+            [0, 1].map(|i| cloth.positions[i].x > start[i].x)
+        };
+
+        assert_eq!(drag(&[0], Vector2::new(100.0, 0.0)), [false, false]);
+        assert_eq!(drag(&[0], Vector2::ZERO), [true, false]);
+        // Without a limit any point can be grabbed
+        assert_eq!(drag(&[], Vector2::new(100.0, 0.0)), [false, true]);
+    }
+
+    #[test]
+    fn elapsed_time_adds_up_the_steps() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        run(&mut cloth, 90);
+        assert!((cloth.elapsed() - 90.0 * DT).abs() < 1e-9);
+
+        cloth.set_time_scale(2.0);
+        run(&mut cloth, 10);
+        assert!((cloth.elapsed() - 110.0 * DT).abs() < 1e-9);
+
+        cloth.reset();
+        assert_eq!(cloth.elapsed(), 0.0);
+    }
+
+    #[test]
+    fn anchors_are_the_initial_positions_of_pinned_points() {
+        let mut cloth = Cloth::new(6, 4, 10, 5, 7, 1.0);
+        run(&mut cloth, 30);
+
+        let expected: Vec<Vector2> = (0..cloth.len_points())
+            .filter(|&i| cloth.pinned[i])
+            .map(|i| cloth.initial_positions[i])
+            .collect();
+        assert!(!expected.is_empty());
+        let anchors = cloth.anchor_positions();
+        assert_eq!(anchors.len(), expected.len());
+        assert!(anchors.iter().zip(&expected).all(|(&a, &b)| close(a, b)));
+        assert!(anchors.iter().all(|anchor| anchor.y == 7.0));
+    }
+
+    #[test]
+    fn stick_quad_straddles_the_stick() {
+        // A stick running right, widened 2 to either side
+        let corners = stick_quad(Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0), 4.0);
+        let expected = [(0.0, 2.0), (10.0, 2.0), (10.0, -2.0), (0.0, -2.0)];
+        for (corner, (x, y)) in corners.into_iter().zip(expected) {
+            assert!(close(corner, Vector2::new(x, y)));
+        }
+
+        // A diagonal stick keeps the full width across it
+        let [a, _, _, d] = stick_quad(Vector2::new(1.0, 1.0), Vector2::new(4.0, 5.0), 3.0);
+        assert!((a.distance(&d) - 3.0).abs() < 1e-9);
+        assert!((a - d).dot(&Vector2::new(3.0, 4.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unbroken_cloth_skips_stick_removal() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, 1.0);
+        let sticks = cloth.len_sticks();
+        run(&mut cloth, 20);
+        assert!(!cloth.any_broken);
+        assert_eq!(cloth.len_sticks(), sticks);
+        assert!(cloth.tear_history.is_empty());
+
+        // The first break is still picked up and removed on the next update
+        cloth.positions[24] += Vector2::new(100.0, 100.0);
+        run(&mut cloth, 1);
+        assert!(cloth.any_broken);
+        let broken = cloth.sticks.iter().filter(|stick| stick.broken).count();
+        assert!(broken > 0);
+        run(&mut cloth, 1);
+        assert_eq!(cloth.tear_history.len(), broken);
+        assert!(cloth.len_sticks() <= sticks - broken);
+    }
+
+    #[test]
+    fn jitter_stays_within_magnitude_and_spares_pins() {
+        let mut cloth = Cloth::new(6, 6, 10, 0, 0, 1.0).with_seed(7);
+        let start = cloth.positions.clone();
+        cloth.jitter(2.0);
+
+        let mut moved = 0;
+        for (i, (&position, &start)) in cloth.positions.iter().zip(&start).enumerate() {
+            let offset = position.distance(&start);
+            if cloth.pinned[i] {
+                assert_eq!(offset, 0.0);
+            } else {
+                assert!(offset <= 2.0);
+                moved += usize::from(offset > 0.0);
+            }
+            // Velocity is left alone
+            assert!(close(cloth.velocity(i), Vector2::ZERO));
+        }
+        assert!(moved > 0);
+    }
+
+    #[test]
+    fn square_moment_of_inertia() {
+        // Four unit masses 5 * sqrt(2) from the center of a 10 by 10 square
+        let cloth = Cloth::new(2, 2, 10, 30, 40, 1.0);
+        assert!((cloth.moment_of_inertia() - 200.0).abs() < 1e-9);
+
+        // A 3 by 3 grid adds four points at 10 and four at 10 * sqrt(2) around its middle point
+        let cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        assert!((cloth.moment_of_inertia() - (4.0 * 100.0 + 4.0 * 200.0)).abs() < 1e-9);
+        assert_eq!(Cloth::from_parts(&[], &[]).moment_of_inertia(), 0.0);
+    }
+
+    #[test]
+    fn draw_follows_the_render_mode() {
+        type DrawPath = fn(&Cloth, &mut Draw);
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, 1.0);
+        cloth.positions[15] += Vector2::new(3.0, 2.0);
+        // What ends up in a fresh `Draw` stands in for what would be shown on screen
+        let output = |cloth: &Cloth, path: DrawPath| {
+            let mut draw = Draw::new(100, 100);
+            path(cloth, &mut draw);
+            format!("{draw:?}")
+        };
+
+        assert_eq!(cloth.render_mode(), RenderMode::Wireframe);
+        let paths: [(RenderMode, DrawPath); 5] = [
+            (RenderMode::Wireframe, |cloth, draw| {
+                cloth.draw_wireframe(draw)
+            }),
+            (RenderMode::Filled, |cloth, draw| cloth.draw_filled(draw)),
+            (RenderMode::Stress, |cloth, draw| cloth.draw_stress(draw)),
+            (RenderMode::Outline, |cloth, draw| {
+                cloth.draw_outline(draw, Color::WHITE)
+            }),
+            (RenderMode::FilledOutline, |cloth, draw| {
+                cloth.draw_filled_outline(draw)
+            }),
+        ];
+        let mut outputs = Vec::new();
+        for (mode, path) in paths {
+            cloth.set_render_mode(mode);
+            let drawn = output(&cloth, |cloth, draw| cloth.draw(draw));
+            assert_eq!(drawn, output(&cloth, path), "{mode:?}");
+            outputs.push(drawn);
+        }
+        for (i, a) in outputs.iter().enumerate() {
+            assert!(outputs[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn color_callback_runs_once_per_intact_stick() {
+        use std::cell::RefCell;
+
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        cloth.tear_stick(2);
+        let calls = RefCell::new(Vec::new());
+        let mut draw = Draw::new(100, 100);
+        cloth.draw_colored(&mut draw, |i, p1, p2| {
+            calls.borrow_mut().push((i, p1, p2));
             Color::WHITE
         });
+
+        let calls = calls.into_inner();
+        let indices: Vec<usize> = calls.iter().map(|&(i, _, _)| i).collect();
+        let expected: Vec<usize> = (0..cloth.len_sticks()).filter(|&i| i != 2).collect();
+        assert_eq!(indices, expected);
+        for (i, p1, p2) in calls {
+            let stick = &cloth.sticks[i];
+            assert!(close(p1, cloth.positions[stick.p1]) && close(p2, cloth.positions[stick.p2]));
+        }
+    }
+
+    #[test]
+    fn tethered_point_swings_within_reach() {
+        let mut cloth = Cloth::from_parts(&[(Vector2::new(30.0, 0.0), false)], &[]);
+        cloth.tether(0, Vector2::ZERO, 30.0, f64::INFINITY);
+
+        let mut leftmost = f64::MAX;
+        for _ in 0..120 {
+            run(&mut cloth, 1);
+            assert!(cloth.positions[0].magnitude() <= 30.0 + 1e-9);
+            leftmost = leftmost.min(cloth.positions[0].x);
+        }
+        // Released level with the anchor, it swings down and out the other side
+        assert!(leftmost < -10.0);
+        assert_eq!(cloth.tethers.len(), 1);
+    }
+
+    #[test]
+    fn yanked_cloth_stretches_on_average() {
+        let mut cloth = Cloth::new(6, 6, 10, 0, 0, f64::INFINITY);
+        cloth.set_solver_iterations(20, 0.0);
+        run(&mut cloth, 300);
+        let settled = cloth.average_stretch();
+        assert!((settled - 1.0).abs() < 0.05);
+
+        cloth.positions[35] += Vector2::new(60.0, 80.0);
+        assert!(cloth.average_stretch() > settled + 0.05);
+    }
+
+    #[test]
+    fn turbulence_follows_the_seed() {
+        let forces = |seed: u64| {
+            let mut cloth = Cloth::new(4, 4, 10, 0, 0, 1.0).with_seed(seed);
+            cloth.set_turbulence(500.0, 30.0);
+            run(&mut cloth, 5);
+            cloth
+                .positions
+                .iter()
+                .map(|&p| {
+                    let force = cloth.force_at(0.0, p, 1.0);
+                    (force.x, force.y)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(forces(3), forces(3));
+        assert_ne!(forces(3), forces(4));
+
+        // Coordinates at the ends of the noise lattice used to overflow
+        let infinity = Vector2::new(f64::INFINITY, f64::NEG_INFINITY);
+        let mut cloth = Cloth::new(2, 2, 10, 0, 0, 1.0);
+        cloth.set_turbulence(500.0, 30.0);
+        cloth.force_at(0.0, infinity, 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "turbulence scale must be positive")]
+    fn turbulence_rejects_a_zero_scale() {
+        let mut cloth = Cloth::new(2, 2, 10, 0, 0, 1.0);
+        cloth.set_turbulence(500.0, 0.0);
+    }
+
+    #[test]
+    fn frozen_cloth_stays_put() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, 1.0);
+        run(&mut cloth, 10);
+        cloth.freeze();
+        let frozen = cloth.positions.clone();
+        let grab = mouse_at(20.0, 20.0, &[MouseButton::Left]);
+        for _ in 0..200 {
+            cloth.update(DT, &grab, Vector2::ZERO);
+        }
+        assert!(cloth.is_frozen());
+        assert!(cloth
+            .positions
+            .iter()
+            .zip(&frozen)
+            .all(|(&a, &b)| close(a, b)));
+    }
+
+    #[test]
+    fn trapezoid_rows_widen_as_configured() {
+        let (width, height) = (5, 4);
+        let cloth = Cloth::trapezoid(width, height, 10, 100, 0, 1.0, |row| {
+            10.0 + 5.0 * row as f64
+        });
+        let width = width as usize;
+        for row in 0..height as usize {
+            let left = cloth.positions[row * width];
+            let right = cloth.positions[row * width + width - 1];
+            let expected = 4.0 * (10.0 + 5.0 * row as f64);
+            assert!((right.x - left.x - expected).abs() < 1e-9);
+            assert!(((left.x + right.x) / 2.0 - 100.0).abs() < 1e-9);
+            assert_eq!(left.y, (row * 10) as f64);
+        }
+
+        // Sticks start out at rest, including the slanted ones between rows
+        for stick in &cloth.sticks {
+            let length = cloth.positions[stick.p1].distance(&cloth.positions[stick.p2]);
+            assert!((length - stick.length).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn points_within_finds_the_enclosed_points() {
+        // Grid spacing 10, so a radius of 10 around (20, 20) takes the point and its 4 neighbors,
+        // leaving the diagonals at ~14.1 outside
+        let cloth = Cloth::new(5, 5, 10, 0, 0, 1.0);
+        let mut found = cloth.points_within(Vector2::new(20.0, 20.0), 10.0);
+        found.sort_unstable();
+        assert_eq!(found, vec![7, 11, 12, 13, 17]);
+
+        assert!(cloth
+            .points_within(Vector2::new(500.0, 500.0), 10.0)
+            .is_empty());
+        assert_eq!(
+            cloth.points_within(Vector2::new(20.0, 20.0), 1000.0).len(),
+            25
+        );
+    }
+
+    #[test]
+    fn disc_connects_rings_and_segments_symmetrically() {
+        let (rings, segments) = (3, 8);
+        let center = Vector2::new(100.0, 100.0);
+        let cloth = Cloth::disc(rings, segments, 30.0, center, 1.0, DiscPin::Rim);
+        assert_eq!(cloth.positions.len(), 1 + rings * segments);
+        assert_eq!(cloth.sticks.len(), 2 * rings * segments);
+
+        // Every point of a ring sits at the same radius with the same stick lengths
+        for ring in 1..=rings {
+            let points = 1 + (ring - 1) * segments..1 + ring * segments;
+            for i in points.clone() {
+                let radius = cloth.positions[i].distance(&center);
+                assert!((radius - 10.0 * ring as f64).abs() < 1e-9);
+                assert_eq!(cloth.pinned[i], ring == rings);
+            }
+            let lengths: Vec<f64> = cloth
+                .sticks
+                .iter()
+                .filter(|stick| points.contains(&stick.p1))
+                .map(|stick| stick.length)
+                .collect();
+            assert_eq!(lengths.len(), 2 * segments);
+            let (outward, around) = (lengths[0], lengths[1]);
+            for pair in lengths.chunks(2) {
+                assert!((pair[0] - outward).abs() < 1e-9);
+                assert!((pair[1] - around).abs() < 1e-9);
+            }
+        }
+
+        // The center connects to every point of the first ring
+        let spokes = cloth.sticks.iter().filter(|stick| stick.p2 == 0).count();
+        assert_eq!(spokes, segments);
+        assert!(!cloth.pinned[0]);
+    }
+
+    #[test]
+    fn last_drag_force_reports_the_clamped_drag() {
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 5.0);
+        cloth.set_drag_strength(100.0);
+        assert!(close(cloth.last_drag_force(), Vector2::ZERO));
+
+        // A small move passes through, a big one is clamped to the elasticity on each axis
+        let mouse = mouse_at(10.0, 10.0, &[MouseButton::Left]);
+        cloth.update(DT, &mouse, Vector2::new(8.0, 11.0));
+        assert!(close(cloth.last_drag_force(), Vector2::new(200.0, -100.0)));
+        cloth.update(DT, &mouse, Vector2::new(-40.0, 30.0));
+        assert!(close(cloth.last_drag_force(), Vector2::new(500.0, -500.0)));
+
+        // Letting go stops the drag
+        cloth.update(DT, &mouse_at(10.0, 10.0, &[]), Vector2::new(-40.0, 30.0));
+        assert!(close(cloth.last_drag_force(), Vector2::ZERO));
+    }
+
+    #[test]
+    fn fit_transform_letterboxes_the_bounds() {
+        // 40 by 20 in a square viewport fills the width and leaves bars above and below
+        let cloth = Cloth::new(5, 3, 10, 0, 0, 1.0);
+        let camera = cloth.fit_transform((200.0, 200.0));
+        assert!((camera.zoom - 5.0).abs() < 1e-9);
+        let (min, max) = cloth.bounds().unwrap();
+        assert!(close(camera.world_to_screen(min), Vector2::new(0.0, 50.0)));
+        assert!(close(
+            camera.world_to_screen(max),
+            Vector2::new(200.0, 150.0)
+        ));
+
+        // A wide viewport fills the height instead
+        let camera = cloth.fit_transform((400.0, 100.0));
+        assert!(close(camera.world_to_screen(min), Vector2::new(100.0, 0.0)));
+        assert!(close(
+            camera.world_to_screen(max),
+            Vector2::new(300.0, 100.0)
+        ));
+    }
+
+    #[test]
+    fn auto_recover_resets_a_blown_up_cloth() {
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, 1.0);
+        cloth.set_auto_recover(true);
+        let grid = cloth.positions.clone();
+        run(&mut cloth, 5);
+        cloth.positions[9] = Vector2::new(f64::NAN, 0.0);
+        run(&mut cloth, 1);
+        assert!(cloth.is_finite());
+        assert_eq!(cloth.step_count, 0);
+        assert!(cloth
+            .positions
+            .iter()
+            .zip(&grid)
+            .all(|(&a, &b)| close(a, b)));
+
+        // Without the watchdog the NaN spreads through the sticks instead
+        let mut cloth = Cloth::new(4, 4, 10, 0, 0, 1.0);
+        cloth.positions[9] = Vector2::new(f64::NAN, 0.0);
+        run(&mut cloth, 1);
+        assert!(!cloth.is_finite());
+    }
+
+    #[test]
+    fn stick_damping_settles_an_oscillation_sooner() {
+        let frames_to_settle = |damping: f64| {
+            let points = [
+                (Vector2::new(0.0, 0.0), false),
+                (Vector2::new(10.0, 0.0), false),
+            ];
+            let mut cloth = Cloth::from_parts(&points, &[(0, 1, 100.0)]);
+            cloth.set_gravity(Vector2::ZERO);
+            // A soft stick springs back over several updates instead of snapping back at once
+            cloth.set_stiffness(0.1);
+            cloth.set_stick_damping(damping);
+            cloth.prev_positions[0] = Vector2::new(-1.0, 0.0);
+            cloth.prev_positions[1] = Vector2::new(11.0, 0.0);
+            (1..=1000)
+                .find(|_| {
+                    run(&mut cloth, 1);
+                    let length = cloth.positions[0].distance(&cloth.positions[1]);
+                    let speed = cloth.velocity(0).magnitude() + cloth.velocity(1).magnitude();
+                    (length - 10.0).abs() < 0.01 && speed < 0.01
+                })
+                .unwrap_or(1000)
+        };
+        let (free, damped) = (frames_to_settle(0.0), frames_to_settle(0.5));
+        assert!(damped < free);
+    }
+
+    #[test]
+    fn normal_run_stays_finite() {
+        let mut cloth = Cloth::new(10, 10, 10, 0, 0, 5.0);
+        cloth.set_wind_shear(Vector2::new(50.0, 0.0), Vector2::ZERO);
+        let drag = mouse_at(45.0, 45.0, &[MouseButton::Left]);
+        for step in 0..500 {
+            let prev_mouse = Vector2::new(45.0 - (step % 7) as f64, 45.0);
+            cloth.update(DT, &drag, prev_mouse);
+            assert!(cloth.is_finite(), "step {step} left a non-finite point");
+        }
+
+        cloth.prev_positions[3].y = f64::INFINITY;
+        assert!(!cloth.is_finite());
+    }
+
+    #[test]
+    fn csv_export_has_a_row_per_point() {
+        let cloth = Cloth::new(3, 2, 10, 5, 0, 1.0);
+        let path = std::env::temp_dir().join(format!("cloth-sim-{}.csv", std::process::id()));
+        cloth.export_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 1 + cloth.positions.len());
+        assert_eq!(lines[0], "index,x,y,pinned");
+        assert_eq!(lines[1], "0,5,0,true");
+        assert_eq!(lines[2], "1,15,0,false");
+        assert_eq!(lines[6], "5,25,10,false");
+    }
+
+    #[test]
+    fn gravity_eases_in_over_the_rampup() {
+        let gravity = Vector2::new(0.0, 500.0);
+        let mut cloth = Cloth::new(2, 2, 10, 0, 0, 1.0).with_gravity_rampup(1.0);
+        cloth.set_gravity(gravity);
+        assert!(close(
+            cloth.force_at(0.0, Vector2::ZERO, 1.0),
+            Vector2::ZERO
+        ));
+        run(&mut cloth, 30);
+        assert!(close(
+            cloth.force_at(0.0, Vector2::ZERO, 1.0),
+            gravity * 0.5
+        ));
+        run(&mut cloth, 30);
+        assert!(close(cloth.force_at(0.0, Vector2::ZERO, 1.0), gravity));
+        run(&mut cloth, 30);
+        assert!(close(cloth.force_at(0.0, Vector2::ZERO, 1.0), gravity));
+    }
+
+    #[test]
+    fn clone_steps_independently_of_the_original() {
+        let mut original = Cloth::new(4, 4, 10, 0, 0, 1.0);
+        original.set_post_iteration(Box::new(|points: &mut [PointView]| {
+            for point in points.iter_mut().filter(|point| !point.pinned()) {
+                point.position.x += 1.0;
+            }
+        }));
+        let before = original.positions.clone();
+
+        let mut copy = original.clone();
+        assert!(copy.post_iteration.0.is_none());
+        run(&mut copy, 20);
+        assert!(copy
+            .positions
+            .iter()
+            .zip(&before)
+            .any(|(&a, &b)| !close(a, b)));
+        assert_eq!(copy.step_count, 20);
+        assert!(original
+            .positions
+            .iter()
+            .zip(&before)
+            .all(|(&a, &b)| close(a, b)));
+        assert_eq!(original.step_count, 0);
+
+        // The original keeps its hook
+        run(&mut original, 1);
+        assert!(original.positions[5].x > before[5].x);
+    }
+
+    #[test]
+    fn lowest_point_hangs_from_the_bottom_row() {
+        let mut cloth = Cloth::new(5, 5, 10, 0, 0, 5.0);
+        run(&mut cloth, 200);
+        let (index, position) = cloth.lowest_point().unwrap();
+        assert!(index >= 20, "point {index} isn't on the bottom row");
+        assert!(close(position, cloth.positions[index]));
+        assert!(cloth.positions.iter().all(|p| p.y <= position.y));
+        assert!(position.y > 40.0);
+
+        assert!(Cloth::from_parts(&[], &[]).lowest_point().is_none());
+    }
+
+    #[test]
+    fn soft_pinned_grid_point_sags_a_little() {
+        let sag = |stiffness: f64| {
+            let mut cloth = Cloth::new(3, 3, 10, 0, 0, 5.0);
+            cloth.soft_pin_in_grid(1, 0, stiffness);
+            run(&mut cloth, 300);
+            assert!(close(cloth.positions[0], cloth.initial_positions[0]));
+            cloth.positions[1].distance(&cloth.initial_positions[1])
+        };
+        let (free, soft) = (sag(0.0), sag(0.5));
+        assert!(soft > 1e-3, "the soft pin held exactly at {soft}");
+        assert!(soft < free / 2.0);
+    }
+
+    #[test]
+    fn velocity_at_a_point_is_its_velocity() {
+        let mut cloth = Cloth::unpinned(4, 4, 10, 0, 0, 1.0);
+        for i in 0..cloth.positions.len() {
+            cloth.prev_positions[i] -= Vector2::new(i as f64, (i % 3) as f64);
+        }
+        for i in [0, 5, 6, 15] {
+            assert!(close(
+                cloth.velocity_at(cloth.positions[i]),
+                cloth.velocity(i)
+            ));
+        }
+
+        // Halfway along an edge is the average of its ends
+        let halfway = (cloth.positions[5] + cloth.positions[6]) / 2.0;
+        let average = (cloth.velocity(5) + cloth.velocity(6)) / 2.0;
+        assert!(close(cloth.velocity_at(halfway), average));
+
+        assert!(close(
+            cloth.velocity_at(Vector2::new(-5.0, 5.0)),
+            Vector2::ZERO
+        ));
+    }
+
+    #[test]
+    fn stiffness_feels_the_same_at_any_iteration_count() {
+        let remaining_stretch = |iterations: usize, tolerance: f64| {
+            let points = [
+                (Vector2::new(0.0, 0.0), false),
+                (Vector2::new(10.0, 0.0), false),
+            ];
+            let mut cloth = Cloth::from_parts(&points, &[(0, 1, 100.0)]);
+            cloth.set_gravity(Vector2::ZERO);
+            cloth.set_stiffness(0.5);
+            cloth.set_solver_iterations(iterations, tolerance);
+            cloth.positions[1].x = 14.0;
+            cloth.prev_positions[1].x = 14.0;
+            run(&mut cloth, 1);
+            cloth.positions[0].distance(&cloth.positions[1]) - 10.0
+        };
+        // A tolerance above the stretch left at the end mustn't cut the iterations short
+        for tolerance in [0.0, 0.25] {
+            for iterations in [1, 2, 5, 20] {
+                let stretch = remaining_stretch(iterations, tolerance);
+                assert!(
+                    (stretch - 2.0).abs() < 1e-9,
+                    "{iterations} iterations left a stretch of {stretch}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn thrown_cloth_drifts_right() {
+        let mut cloth =
+            Cloth::new(4, 4, 10, 0, 0, 1.0).with_initial_velocity(Vector2::new(120.0, 0.0));
+        for i in (0..4).step_by(2) {
+            assert!(close(cloth.velocity(i), Vector2::ZERO));
+        }
+        let start = cloth.positions.clone();
+        run(&mut cloth, 3);
+        for (i, (position, start)) in cloth.positions.iter().zip(&start).enumerate() {
+            if !cloth.pinned[i] {
+                assert!(position.x > start.x, "point {i} didn't drift right");
+            }
+        }
+
+        // Without pins the whole cloth keeps the velocity
+        let mut cloth =
+            Cloth::unpinned(4, 4, 10, 0, 0, 1.0).with_initial_velocity(Vector2::new(120.0, 0.0));
+        run(&mut cloth, 3);
+        let drift = cloth.positions[5].x - start[5].x;
+        // Each step covers a reference step's worth of the velocity, less the air drag
+        let expected: f64 = (1..=3)
+            .map(|step| 120.0 * REFERENCE_DT * 0.95f64.powi(step))
+            .sum();
+        assert!((drift - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn filled_outline_leaves_out_the_interior_sticks() {
+        // A 3 by 3 grid has 8 sticks around its edge and 4 meeting in the middle
+        let cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        let boundary = cloth.boundary_sticks();
+        assert_eq!(boundary.len(), 8);
+        for i in boundary {
+            let Stick { p1, p2, .. } = cloth.sticks[i];
+            assert!(p1 != 4 && p2 != 4, "stick {i} touches the middle point");
+        }
+
+        let output = |path: &dyn Fn(&mut Draw)| {
+            let mut draw = Draw::new(100, 100);
+            path(&mut draw);
+            format!("{draw:?}")
+        };
+        let filled_outline = output(&|draw| cloth.draw_filled_outline(draw));
+        let fill_then_outline = output(&|draw| {
+            cloth.fill_cells(draw, Color::GRAY);
+            cloth.draw_outline(draw, Color::WHITE);
+        });
+        let fill_then_wireframe = output(&|draw| {
+            cloth.fill_cells(draw, Color::GRAY);
+            for stick in &cloth.sticks {
+                stick.render_colored(&cloth.positions, draw, Color::WHITE, cloth.line_style);
+            }
+        });
+        let outline_only = output(&|draw| cloth.draw_outline(draw, Color::WHITE));
+        assert_eq!(filled_outline, fill_then_outline);
+        assert_ne!(filled_outline, fill_then_wireframe);
+        assert_ne!(filled_outline, outline_only);
+    }
+
+    #[test]
+    fn counts_points_and_sticks() {
+        // Each of the 20 rows and 20 columns has 19 sticks
+        let cloth = Cloth::new(20, 20, 10, 0, 0, 1.0);
+        assert_eq!(cloth.len_points(), 400);
+        assert_eq!(cloth.len_sticks(), 760);
+
+        let mut cloth = Cloth::new(20, 20, 10, 0, 0, 1.0);
+        cloth.tear_stick(0);
+        run(&mut cloth, 1);
+        assert_eq!(cloth.len_sticks(), 759);
+    }
+
+    #[test]
+    fn anchors_move_with_the_mouse() {
+        let mut cloth = Cloth::new(5, 3, 10, 0, 0, 5.0);
+        let anchors = cloth.anchor_positions();
+        let delta = Vector2::new(10.0, -5.0);
+        let mouse = mouse_at(50.0, 20.0, &[]);
+        let prev_mouse = Vector2::new(50.0, 20.0) - delta;
+
+        cloth.update(DT, &mouse, prev_mouse);
+        assert!(cloth
+            .anchor_positions()
+            .iter()
+            .zip(&anchors)
+            .all(|(&a, &b)| close(a, b)));
+
+        cloth.set_anchors_follow_mouse(true);
+        for step in 1..=3 {
+            cloth.update(DT, &mouse, prev_mouse);
+            let moved = delta * step as f64;
+            for (i, anchor) in (0..5).step_by(2).zip(&anchors) {
+                assert!(close(cloth.anchor_positions()[i / 2], *anchor + moved));
+                assert!(close(cloth.positions[i], *anchor + moved));
+            }
+        }
+    }
+
+    #[test]
+    fn cut_breaks_the_sticks_a_point_holds() {
+        // Spaced wider than the cursor so a click selects a single point
+        let cut_at = |index: usize| {
+            let mut cloth = Cloth::new(3, 3, 40, 0, 0, 100.0);
+            let pairs = |cloth: &Cloth| -> Vec<(usize, usize)> {
+                cloth
+                    .sticks
+                    .iter()
+                    .map(|stick| (stick.p1.min(stick.p2), stick.p1.max(stick.p2)))
+                    .collect()
+            };
+            let before = pairs(&cloth);
+            let position = cloth.positions[index];
+            let mouse = mouse_at(position.x as f32, position.y as f32, &[MouseButton::Right]);
+            cloth.update(DT, &mouse, position);
+            let after = pairs(&cloth);
+            let mut cut: Vec<(usize, usize)> = before
+                .into_iter()
+                .filter(|pair| !after.contains(pair))
+                .collect();
+            cut.sort_unstable();
+            cut
+        };
+        // The right and lower neighbors, not the left and upper ones
+        assert_eq!(cut_at(4), vec![(4, 5), (4, 7)]);
+        // The last column and row fall back to the left and upper neighbors
+        assert_eq!(cut_at(8), vec![(5, 8), (7, 8)]);
+        assert_eq!(cut_at(2), vec![(1, 2), (2, 5)]);
+        assert_eq!(cut_at(6), vec![(3, 6), (6, 7)]);
+    }
+
+    #[test]
+    fn cracks_respect_the_break_margin() {
+        let cracks = |margin: f64| {
+            let points = [
+                (Vector2::new(0.0, 0.0), false),
+                (Vector2::new(10.0, 0.0), false),
+                (Vector2::new(20.0, 0.0), false),
+            ];
+            let mut cloth =
+                Cloth::from_parts(&points, &[(0, 1, 0.1), (1, 2, 0.1)]).with_break_margin(margin);
+            // Strained past half the elasticity but not past half of it plus the margin
+            cloth.positions[2].x = 20.6;
+            cloth.crack_tips.push(1);
+            cloth.propagate_cracks(1);
+            cloth.sticks[1].broken
+        };
+        assert!(cracks(0.0));
+        assert!(!cracks(0.1));
+    }
+
+    #[test]
+    fn loose_points_can_be_dragged() {
+        // A single point, and two points joined by a stick that breaks at any stretch
+        let single = Cloth::from_parts(&[(Vector2::new(10.0, 10.0), false)], &[]);
+        let points = [
+            (Vector2::new(10.0, 10.0), false),
+            (Vector2::new(100.0, 10.0), false),
+        ];
+        let brittle = Cloth::from_parts(&points, &[(0, 1, 0.0)]);
+
+        for mut cloth in [single, brittle] {
+            cloth.set_gravity(Vector2::ZERO);
+            let mouse = mouse_at(10.0, 10.0, &[MouseButton::Left]);
+            cloth.update(DT, &mouse, Vector2::new(0.0, 10.0));
+            let limit = UNBREAKABLE_DRAG_LIMIT * FORCE_MULTIPLIER;
+            assert!(close(cloth.last_drag_force(), Vector2::new(limit, 0.0)));
+            assert!(cloth.positions[0].x > 10.0);
+        }
+    }
+
+    #[test]
+    fn soft_pins_follow_their_points() {
+        let anchor = Vector2::new(5.0, 5.0);
+        let mut cloth = Cloth::new(3, 3, 10, 0, 0, 1.0);
+        cloth.soft_pin(4, anchor, 2.0);
+        cloth.remove_point(cloth.point_handle(1).unwrap());
+        let (pin_anchor, stiffness) = cloth.soft_pins[3].unwrap();
+        assert!(close(pin_anchor, anchor) && stiffness == 2.0);
+        assert_eq!(cloth.soft_pins.iter().flatten().count(), 1);
+
+        cloth.soft_pin(3, anchor, 0.0);
+        assert!(cloth.soft_pins.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn fragments_start_without_the_hook() {
+        let mut cloth = Cloth::new(4, 3, 10, 0, 0, 1.0);
+        cloth.set_post_iteration(Box::new(|_: &mut [PointView]| {}));
+        // Tear off the first column
+        for i in 0..cloth.sticks.len() {
+            let pair = (cloth.sticks[i].p1 % 4, cloth.sticks[i].p2 % 4);
+            if pair == (1, 0) {
+                cloth.tear_stick(i);
+            }
+        }
+
+        let fragments = cloth.split_into_fragments();
+        assert_eq!(fragments.len(), 2);
+        assert!(fragments
+            .iter()
+            .all(|fragment| fragment.post_iteration.0.is_none()));
     }
 }
@@ -59,6 +59,8 @@ fn setup() -> State {
 fn update(app: &mut App, state: &mut State) {
     let dt = app.timer.delta().as_secs_f64();
     for cloth in &mut state.cloths {
+        // Hold shift to drag the cloths around by their pins
+        cloth.set_anchors_follow_mouse(app.keyboard.shift());
         cloth.update(dt, &app.mouse, state.prev_mouse_position);
     }
 
@@ -30,6 +30,101 @@ impl Vector2 {
     pub fn distance(&self, other: &Vector2) -> f64 {
         (*self - *other).magnitude()
     }
+
+    pub fn dot(&self, other: &Vector2) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the vector with a magnitude of 1, or zero if the vector is zero
+    pub fn normalized(&self) -> Vector2 {
+        let magnitude = self.magnitude();
+        if magnitude == 0.0 {
+            Vector2::ZERO
+        } else {
+            *self / magnitude
+        }
+    }
+
+    /// Returns the vector rotated by 90 degrees
+    pub fn perpendicular(&self) -> Vector2 {
+        Vector2::new(-self.y, self.x)
+    }
+}
+
+/// Returns the point on the segment from `a` to `b` closest to `p`
+pub fn closest_point_on_segment(p: Vector2, a: Vector2, b: Vector2) -> Vector2 {
+    let ab = b - a;
+    let length_sq = ab.magnitude_squared();
+    if length_sq == 0.0 {
+        return a;
+    }
+    let t = ((p - a).dot(&ab) / length_sq).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+/// Returns the distance from `p` to the closest point on the segment from `a` to `b`
+pub fn point_segment_distance(p: Vector2, a: Vector2, b: Vector2) -> f64 {
+    p.distance(&closest_point_on_segment(p, a, b))
+}
+
+/// Whether the segment from `a1` to `a2` crosses the segment from `b1` to `b2`. Segments that only
+/// touch at an end or lie along each other don't count as crossing.
+pub fn segments_intersect(a1: Vector2, a2: Vector2, b1: Vector2, b2: Vector2) -> bool {
+    // Which side of the line through p and q the point r is on
+    let side = |p: Vector2, q: Vector2, r: Vector2| (q - p).perpendicular().dot(&(r - p));
+    side(b1, b2, a1) * side(b1, b2, a2) < 0.0 && side(a1, a2, b1) * side(a1, a2, b2) < 0.0
+}
+
+/// Finds the coordinates `(u, v)` in `[0, 1]` of `p` within the quad with corners `a`, `b`, `c`
+/// and `d` in order around it, such that blending the corners bilinearly by them gives `p`, with
+/// `u` running from `a` to `b` and `v` from `a` to `d`. Returns None if `p` is outside the quad.
+pub fn inverse_bilinear(
+    p: Vector2,
+    a: Vector2,
+    b: Vector2,
+    c: Vector2,
+    d: Vector2,
+) -> Option<(f64, f64)> {
+    let cross = |a: Vector2, b: Vector2| a.perpendicular().dot(&b);
+    let e = b - a;
+    let f = d - a;
+    let g = a - b + c - d;
+    let h = p - a;
+
+    // v solves k2 * v^2 + k1 * v + k0 = 0, which is linear when opposite sides are parallel
+    let k2 = cross(g, f);
+    let k1 = cross(e, f) + cross(h, g);
+    let k0 = cross(h, e);
+
+    // Given v, solve for u along whichever axis is better conditioned
+    let u_for = |v: f64| {
+        let denominator = e + g * v;
+        if denominator.x.abs() > denominator.y.abs() {
+            (h.x - f.x * v) / denominator.x
+        } else {
+            (h.y - f.y * v) / denominator.y
+        }
+    };
+    // Allow for rounding so points right on an edge or corner still count as inside
+    let range = -1e-6..=1.0 + 1e-6;
+    let inside = |(u, v): (f64, f64)| range.contains(&u) && range.contains(&v);
+    let clamp = |(u, v): (f64, f64)| (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+
+    if k2.abs() < 1e-9 {
+        let v = -k0 / k1;
+        return Some((u_for(v), v)).filter(|&uv| inside(uv)).map(clamp);
+    }
+
+    let discriminant = k1 * k1 - 4.0 * k0 * k2;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [(-k1 - root) / (2.0 * k2), (-k1 + root) / (2.0 * k2)]
+        .into_iter()
+        .map(|v| (u_for(v), v))
+        .find(|&uv| inside(uv))
+        .map(clamp)
 }
 
 impl Default for Vector2 {
@@ -0,0 +1,11 @@
+use std::time::Duration;
+
+/// Time spent in each phase of the most recent `Cloth::update`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameTimings {
+    // Applying forces and integrating the points
+    pub integration: Duration,
+    // Solving and tearing the sticks, along with everything done after
+    pub constraints: Duration,
+    pub total: Duration,
+}
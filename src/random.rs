@@ -0,0 +1,87 @@
+/// Small seeded pseudo-random number generator (xorshift64*), so that randomized effects play
+/// out the same way every run for a given seed
+#[derive(Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // The state must never be zero or every number after it is zero too
+        Rng {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniformly distributed number in `[0, 1)`
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Smooth noise in `[-1, 1]` varying over space and time, the sum of `octaves` layers of value
+/// noise each with twice the detail and half the strength of the last. The same seed always gives
+/// the same noise.
+pub(crate) fn layered_noise(seed: u64, x: f64, y: f64, t: f64, octaves: u32) -> f64 {
+    let mut total = 0.0;
+    let mut weight = 1.0;
+    let mut frequency = 1.0;
+    let mut weights = 0.0;
+    for octave in 0..octaves {
+        let layer_seed = seed.wrapping_add(octave as u64);
+        total += value_noise(layer_seed, x * frequency, y * frequency, t * frequency) * weight;
+        weights += weight;
+        weight /= 2.0;
+        frequency *= 2.0;
+    }
+    total / weights
+}
+
+/// Interpolates between random values at the corners of the unit cube containing the point
+fn value_noise(seed: u64, x: f64, y: f64, t: f64) -> f64 {
+    let (x0, y0, t0) = (x.floor(), y.floor(), t.floor());
+    // Smoothstep the fractions so the noise has no visible creases along the cube faces
+    let fade = |f: f64| f * f * (3.0 - 2.0 * f);
+    let (fx, fy, ft) = (fade(x - x0), fade(y - y0), fade(t - t0));
+    let (ix, iy, it) = (x0 as i64, y0 as i64, t0 as i64);
+
+    let lerp = |a: f64, b: f64, f: f64| a + (b - a) * f;
+    let corner = |dx: i64, dy: i64, dt: i64| {
+        // Infinite coordinates saturate to the ends of the lattice, so step around it instead of
+        // overflowing
+        lattice_value(
+            seed,
+            ix.wrapping_add(dx),
+            iy.wrapping_add(dy),
+            it.wrapping_add(dt),
+        )
+    };
+    let plane = |dt: i64| {
+        lerp(
+            lerp(corner(0, 0, dt), corner(1, 0, dt), fx),
+            lerp(corner(0, 1, dt), corner(1, 1, dt), fx),
+            fy,
+        )
+    };
+    lerp(plane(0), plane(1), ft)
+}
+
+/// Random value in `[-1, 1)` for a point of the integer lattice
+fn lattice_value(seed: u64, x: i64, y: i64, t: i64) -> f64 {
+    let mut hash = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (t as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    // Finish with the splitmix64 mixer so nearby lattice points get unrelated values
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^= hash >> 31;
+    (hash >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
@@ -0,0 +1,24 @@
+use crate::math::Vector2;
+
+/// Copy of the state of a `Cloth` at one point in time, taken with `Cloth::snapshot`
+#[derive(Clone, Debug)]
+pub struct ClothSnapshot {
+    pub positions: Vec<Vector2>,
+    // Whether each stick was broken
+    pub broken: Vec<bool>,
+}
+
+/// Largest distance between the positions of the same point in two snapshots.
+///
+/// Snapshots with different numbers of points are infinitely far apart.
+pub fn diff(a: &ClothSnapshot, b: &ClothSnapshot) -> f64 {
+    if a.positions.len() != b.positions.len() {
+        return f64::INFINITY;
+    }
+
+    a.positions
+        .iter()
+        .zip(&b.positions)
+        .map(|(a, b)| a.distance(b))
+        .fold(0.0, f64::max)
+}